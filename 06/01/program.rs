@@ -2,42 +2,26 @@ use std::env;
 use std::fs::File;
 use std::io::{self, BufRead};
 use std::path::Path;
-use std::error::Error;
-use std::collections::VecDeque;
 
-fn parse_line(line: &str) -> Result<VecDeque<u32>, Box<dyn Error>> {
-  let mut position: Vec<u32> = vec![0; 9];
+#[path = "../../common/biguint.rs"]
+mod biguint;
+#[path = "../../common/matrix.rs"]
+mod matrix;
+#[path = "../lanternfish.rs"]
+mod lanternfish;
 
-  for part in line.split(',') {
-    position[part.parse::<usize>()?] += 1;
-  }
-
-  Ok(VecDeque::from(position))
-}
-
-fn step(state: &mut VecDeque<u32>) {
-  let doubling = state.pop_front().unwrap();
-
-  state.push_back(doubling);
-  state[6] += doubling;
-}
+use lanternfish::Options;
 
 fn main() {
-  let args: Vec<String> = env::args().collect();
+  let (options, files) = Options::parse(env::args().skip(1), 80);
 
-  if args.len() != 2 {
-    panic!("Expected exactly 1 argument, got {}", args.len());
+  if files.len() != 1 {
+    panic!("Expected exactly 1 input file, got {}", files.len());
   }
 
-  if let Ok(lines) = read_lines(&args[1]) {
+  if let Ok(lines) = read_lines(&files[0]) {
     for line in lines {
-      let mut state = parse_line(&line.unwrap()).unwrap();
-
-      for _i in 0..80 {
-        step(&mut state);
-      }
-
-      println!("{}", state.iter().sum::<u32>());
+      lanternfish::run(&line.unwrap(), &options);
     }
   } else {
     panic!("Failed to read file");
@@ -50,4 +34,4 @@ fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
 where P: AsRef<Path>, {
     let file = File::open(filename)?;
     Ok(io::BufReader::new(file).lines())
-}
\ No newline at end of file
+}
@@ -0,0 +1,186 @@
+use std::cmp;
+use std::process;
+
+use crate::matrix::{Arithmetic, Exact, Matrix, Modular};
+
+/// The most days populations are counted exactly for
+///
+/// Populations grow by a factor of about 1.09 a day with the puzzle's timers, so the exact population after
+/// `n` days has about `n / 27` digits. A day count like 10^18 can never be counted exactly, its population
+/// would have about 10^16 digits. Past this many days the population is only counted modulo some number,
+/// which works up to the largest day count that fits a `u64`.
+pub const MAX_EXACT_DAYS: u64 = 1_000_000;
+
+// A day is a product of square matrices with a row per timer, which gets slow quickly for long timers
+pub const MAX_TIMER: usize = 64;
+
+pub const USAGE: &str = "\
+Usage: [options] <input file>
+
+  --days N       number of days to simulate
+  --reset N      timer of a fish after it spawns (6)
+  --newborn N    timer of a newly spawned fish (8)
+  --modulus M    count the population modulo M
+  --series       print the population of every day as CSV, day,population
+
+Timers go up to 64. Populations are exact up to 1000000 days, past that they have too many digits to
+count (about 10^16 at 10^18 days) and --modulus is required.";
+
+// Parses the fish timers, like `3,4,3,1,2`
+pub fn parse(line: &str) -> Result<Vec<usize>, String> {
+  line.trim().split(',').map(|part| {
+    match part.trim().parse::<usize>() {
+      Ok(timer) if timer <= MAX_TIMER => Ok(timer),
+      Ok(timer) => Err(format!("timer {} is above the maximum of {}", timer, MAX_TIMER)),
+      Err(_) => Err(format!("invalid timer {}", part)),
+    }
+  }).collect()
+}
+
+/// Lanternfish grouped by timer
+///
+/// A fish with its timer at 0 resets it to `reset` and spawns a fish with its timer at `newborn`, any other
+/// fish counts down by one. Fish with the same timer do the same thing, so a school is the number of fish
+/// with each timer and a day is a linear map on those counts: `days` days are the day's matrix raised to
+/// the power `days`.
+pub struct Model {
+  pub reset: usize,
+  pub newborn: usize,
+}
+
+impl Model {
+  pub fn new(reset: usize, newborn: usize) -> Result<Model, String> {
+    if cmp::max(reset, newborn) > MAX_TIMER {
+      return Err(format!("timers can be at most {}, got reset {} and newborn {}", MAX_TIMER, reset, newborn));
+    }
+
+    Ok(Model { reset, newborn })
+  }
+
+  // The number of fish with each timer. Timers go up to the reset and newborn timers, or the largest timer
+  // in the school if that's above both.
+  pub fn school<A: Arithmetic>(&self, fish: &[usize], arith: &A) -> Vec<A::Value> {
+    let timers = cmp::max(cmp::max(self.reset, self.newborn), fish.iter().copied().max().unwrap_or(0)) + 1;
+    let mut school = vec![arith.zero(); timers];
+
+    for timer in fish.iter() {
+      school[*timer] = arith.add(&school[*timer], &arith.value(1));
+    }
+
+    school
+  }
+
+  // Timers each timer turns into after a day
+  fn successors(&self, timers: usize) -> Vec<Vec<usize>> {
+    (0..timers).map(|timer| if timer == 0 { vec![self.reset, self.newborn] } else { vec![timer - 1] }).collect()
+  }
+
+  pub fn step<A: Arithmetic>(&self, school: &[A::Value], arith: &A) -> Vec<A::Value> {
+    let mut next = vec![arith.zero(); school.len()];
+
+    for (timer, successors) in self.successors(school.len()).iter().enumerate() {
+      for to in successors.iter() {
+        next[*to] = arith.add(&next[*to], &school[timer]);
+      }
+    }
+
+    next
+  }
+
+  // The school after `days` days in O(log days) matrix products
+  pub fn after<A: Arithmetic>(&self, school: &[A::Value], days: u64, arith: &A) -> Vec<A::Value> {
+    let day = Matrix::transitions(&self.successors(school.len()), arith);
+
+    day.pow(days, arith).apply(school, arith)
+  }
+
+  // Calls `visit` with the population on every day from 0 up to and including `days`
+  pub fn series<A: Arithmetic, F: FnMut(u64, &A::Value)>(&self, school: &[A::Value], days: u64, arith: &A, mut visit: F) {
+    let mut school = school.to_vec();
+
+    for day in 0..=days {
+      visit(day, &population(&school, arith));
+
+      if day < days {
+        school = self.step(&school, arith);
+      }
+    }
+  }
+}
+
+pub fn population<A: Arithmetic>(school: &[A::Value], arith: &A) -> A::Value {
+  school.iter().fold(arith.zero(), |sum, count| arith.add(&sum, count))
+}
+
+// Command line options of both parts, which only differ in the default number of days
+pub struct Options {
+  pub model: Model,
+  pub days: u64,
+  pub modulus: Option<u64>,
+  pub series: bool,
+}
+
+impl Options {
+  // Returns the options and the remaining arguments, which are the input files. Exits with the usage text
+  // on --help and with an error for options that can't be counted.
+  pub fn parse<I: Iterator<Item = String>>(mut args: I, days: u64) -> (Options, Vec<String>) {
+    let mut days = days;
+    let mut reset = 6_usize;
+    let mut newborn = 8_usize;
+    let mut modulus: Option<u64> = None;
+    let mut series = false;
+    let mut files: Vec<String> = Vec::new();
+
+    while let Some(arg) = args.next() {
+      match arg.as_str() {
+        "--days" => days = args.next().and_then(|v| v.parse().ok()).expect("Expected a number of days"),
+        "--reset" => reset = args.next().and_then(|v| v.parse().ok()).expect("Expected a reset timer"),
+        "--newborn" => newborn = args.next().and_then(|v| v.parse().ok()).expect("Expected a newborn timer"),
+        "--modulus" => modulus = Some(args.next().and_then(|v| v.parse().ok()).filter(|v| *v > 0).expect("Expected a positive modulus")),
+        "--series" => series = true,
+        "--help" => {
+          println!("{}", USAGE);
+          process::exit(0);
+        },
+        _ => files.push(arg),
+      }
+    }
+
+    if modulus.is_none() && days > MAX_EXACT_DAYS {
+      eprintln!("Can't count exactly past {} days, the population would have too many digits\n\n{}", MAX_EXACT_DAYS, USAGE);
+      process::exit(1);
+    }
+
+    let model = Model::new(reset, newborn).unwrap_or_else(|err| {
+      eprintln!("Invalid timers, {}", err);
+      process::exit(1);
+    });
+
+    (Options { model, days, modulus, series }, files)
+  }
+}
+
+fn count<A: Arithmetic>(options: &Options, fish: &[usize], arith: &A) {
+  let model = &options.model;
+  let school = model.school(fish, arith);
+
+  if options.series {
+    println!("day,population");
+    model.series(&school, options.days, arith, |day, population| println!("{},{}", day, population));
+  } else {
+    println!("{}", population(&model.after(&school, options.days, arith), arith));
+  }
+}
+
+// Prints the population grown from the school on `line`
+pub fn run(line: &str, options: &Options) {
+  let fish = parse(line).unwrap_or_else(|err| {
+    eprintln!("Invalid school, {}", err);
+    process::exit(1);
+  });
+
+  match options.modulus {
+    Some(modulus) => count(options, &fish, &Modular(modulus)),
+    None => count(options, &fish, &Exact),
+  }
+}
@@ -2,99 +2,71 @@ use std::env;
 use std::fs::File;
 use std::io::{self, BufRead};
 use std::path::Path;
-use std::collections::HashSet;
-use std::convert::TryInto;
+use std::process;
 
-#[derive(PartialEq,Eq,Hash,Debug,Copy,Clone)]
-struct Point {
-  x: u32,
-  y: u32,
-}
-
-fn flip(offset: u32, val: u32) -> u32 {
-  (2 * offset).checked_sub(val).unwrap()
-}
+#[path = "../fold.rs"]
+mod fold;
+#[path = "../ocr.rs"]
+mod ocr;
 
-impl Point {
-  fn flip_x(&self, offset: u32) -> Point {
-    if self.x > offset {
-      Point { x: flip(offset, self.x), y: self.y }
-    } else {
-      *self
-    }
-  }
+use fold::Sheet;
 
-  fn flip_y(&self, offset: u32) -> Point {
-    if self.y > offset {
-      Point { x: self.x, y: flip(offset, self.y) }
-    } else {
-      *self
+fn main() {
+  let mut bitmap = false;
+  let mut steps = false;
+  let mut back = 0_usize;
+  let mut files: Vec<String> = Vec::new();
+
+  let mut args = env::args().skip(1);
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "--bitmap" => bitmap = true,
+      "--steps" => steps = true,
+      "--back" => back = args.next().and_then(|v| v.parse().ok()).expect("Expected a number of folds to undo"),
+      _ => files.push(arg),
     }
   }
-}
-
-fn main() {
-  let args: Vec<String> = env::args().collect();
 
-  if args.len() != 2 {
-    panic!("Expected exactly 1 argument, got {}", args.len());
+  if files.len() != 1 {
+    panic!("Expected exactly 1 input file, got {}", files.len());
   }
 
-  if let Ok(lines) = read_lines(&args[1]) {
-    let mut dots: HashSet<Point> = HashSet::new();
+  if let Ok(lines) = read_lines(&files[0]) {
+    let (dots, folds) = fold::parse(lines.map(|line| line.unwrap())).unwrap_or_else(|err| {
+      eprintln!("Invalid instructions, {}", err);
+      process::exit(1);
+    });
 
-    for line in lines {
-      let l = line.unwrap();
-      
-      if ! l.starts_with("fold along") {
-        if l.len() > 0 {
-          let parts = l.split(',').collect::<Vec<_>>();
+    let mut sheet = Sheet::new(dots);
 
-          let x = parts[0].parse::<u32>().unwrap();
-          let y = parts[1].parse::<u32>().unwrap();
+    for fold in folds {
+      sheet.fold(fold);
 
-          dots.insert(Point { x: x, y: y });
-        }
-      } else {
-        let offset: u32;
-        {
-          let offset_str = &l[13..];
-
-          offset = offset_str.parse::<u32>().unwrap();
-        }
-
-        let mut new_dots: HashSet<Point> = HashSet::new();
-
-        match l.chars().take(12).last().unwrap() {
-          'x' => {
-            for d in dots {
-              new_dots.insert(d.flip_x(offset));
-            }
-          },
-          'y' => {
-            for d in dots {
-              new_dots.insert(d.flip_y(offset));
-            }
-          },
-          c => panic!("unexpected axis \"{}\"", c),
-        }
+      if steps {
+        println!("{}: {} dots", fold, sheet.dots.len());
+        println!("{}", sheet.render());
+      }
+    }
 
-        dots = new_dots;
+    for _ in 0..back {
+      match sheet.undo() {
+        Some(fold) => println!("undo {}: {} dots", fold, sheet.dots.len()),
+        None => break,
       }
     }
 
-    let max_x: usize = dots.iter().map(|p| p.x).max().unwrap().try_into().unwrap();
-    let max_y: usize = dots.iter().map(|p| p.y).max().unwrap().try_into().unwrap();
+    let code = ocr::recognise(sheet.dots.iter().map(|p| (p.x, p.y)));
 
-    let mut rows = (0..(max_y + 1)).map(|_| vec![' '; max_x + 1]).collect::<Vec<_>>();
+    // Fall back to the bitmap when the letters can't be read
+    if let Some(code) = code.as_ref() {
+      println!("{}", code);
 
-    for Point {x, y} in dots {
-      rows[y as usize][x as usize] = '#';
+      if !bitmap {
+        return;
+      }
     }
 
-    for row in rows {
-      println!("{}", row.into_iter().collect::<String>());
-    }
+    print!("{}", sheet.render());
   } else {
     panic!("Failed to read file");
   }
@@ -106,4 +78,4 @@ fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
 where P: AsRef<Path>, {
     let file = File::open(filename)?;
     Ok(io::BufReader::new(file).lines())
-}
\ No newline at end of file
+}
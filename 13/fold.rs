@@ -0,0 +1,141 @@
+#![allow(dead_code)]
+
+use std::collections::HashSet;
+use std::fmt;
+
+#[derive(PartialEq,Eq,Hash,Debug,Copy,Clone)]
+pub struct Point {
+  pub x: i32,
+  pub y: i32,
+}
+
+#[derive(PartialEq,Eq,Debug,Copy,Clone)]
+pub enum Axis {
+  X,
+  Y,
+}
+
+// Folds the part of the sheet past `line` over onto the part before it
+#[derive(PartialEq,Eq,Debug,Copy,Clone)]
+pub struct Fold {
+  pub axis: Axis,
+  pub line: i32,
+}
+
+impl fmt::Display for Fold {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let axis = match self.axis {
+      Axis::X => 'x',
+      Axis::Y => 'y',
+    };
+
+    write!(f, "fold along {}={}", axis, self.line)
+  }
+}
+
+impl Fold {
+  // Parses `fold along x=655`
+  pub fn parse(line: &str) -> Result<Fold, String> {
+    let instruction = line.trim().strip_prefix("fold along ").ok_or_else(|| format!("expected a fold, got {}", line))?;
+    let (axis, position) = instruction.split_once('=').ok_or_else(|| format!("expected axis=line, got {}", instruction))?;
+
+    let axis = match axis.trim() {
+      "x" => Axis::X,
+      "y" => Axis::Y,
+      other => return Err(format!("unexpected axis \"{}\"", other)),
+    };
+    let line = position.trim().parse::<i32>().map_err(|_| format!("invalid fold line {}", position))?;
+
+    Ok(Fold { axis, line })
+  }
+
+  // Mirrors points past the line, points before or on it stay put. The sheet can extend past 0 after folding.
+  pub fn apply(&self, point: &Point) -> Point {
+    match self.axis {
+      Axis::X if point.x > self.line => Point { x: 2 * self.line - point.x, y: point.y },
+      Axis::Y if point.y > self.line => Point { x: point.x, y: 2 * self.line - point.y },
+      _ => *point,
+    }
+  }
+}
+
+fn parse_point(line: &str) -> Result<Point, String> {
+  let (x, y) = line.split_once(',').ok_or_else(|| format!("expected x,y, got {}", line))?;
+  let x = x.trim().parse::<i32>().map_err(|_| format!("invalid x coordinate {}", x))?;
+  let y = y.trim().parse::<i32>().map_err(|_| format!("invalid y coordinate {}", y))?;
+
+  Ok(Point { x, y })
+}
+
+// Reads the dots followed by the folds, errors mention the line they're on
+pub fn parse<I: Iterator<Item = String>>(lines: I) -> Result<(HashSet<Point>, Vec<Fold>), String> {
+  let mut dots = HashSet::new();
+  let mut folds = Vec::new();
+
+  for (i, line) in lines.enumerate() {
+    let result = if line.trim().is_empty() {
+      Ok(())
+    } else if line.starts_with("fold") {
+      Fold::parse(&line).map(|fold| folds.push(fold))
+    } else if !folds.is_empty() {
+      Err(String::from("expected a fold, dots come before the folds"))
+    } else {
+      parse_point(&line).map(|point| { dots.insert(point); })
+    };
+
+    result.map_err(|err| format!("line {}: {}", i + 1, err))?;
+  }
+
+  Ok((dots, folds))
+}
+
+/// A transparent sheet with dots, folded one instruction at a time
+///
+/// Folding merges dots, so the sheet keeps the dots from before every fold to be able to undo it.
+pub struct Sheet {
+  pub dots: HashSet<Point>,
+  history: Vec<(Fold, HashSet<Point>)>,
+}
+
+impl Sheet {
+  pub fn new(dots: HashSet<Point>) -> Sheet {
+    Sheet { dots, history: Vec::new() }
+  }
+
+  pub fn fold(&mut self, fold: Fold) {
+    let folded = self.dots.iter().map(|point| fold.apply(point)).collect();
+    let previous = std::mem::replace(&mut self.dots, folded);
+
+    self.history.push((fold, previous));
+  }
+
+  // Unfolds the last fold, returning it
+  pub fn undo(&mut self) -> Option<Fold> {
+    let (fold, previous) = self.history.pop()?;
+    self.dots = previous;
+
+    Some(fold)
+  }
+
+  pub fn folds(&self) -> Vec<Fold> {
+    self.history.iter().map(|(fold, _)| *fold).collect()
+  }
+
+  // Draws the dots as `#`, starting from the top left dot
+  pub fn render(&self) -> String {
+    let (min_x, max_x) = match (self.dots.iter().map(|p| p.x).min(), self.dots.iter().map(|p| p.x).max()) {
+      (Some(min), Some(max)) => (min, max),
+      _ => return String::new(),
+    };
+    let min_y = self.dots.iter().map(|p| p.y).min().unwrap();
+    let max_y = self.dots.iter().map(|p| p.y).max().unwrap();
+
+    let mut rows = (min_y..=max_y).map(|_| vec![' '; (max_x - min_x + 1) as usize]).collect::<Vec<_>>();
+
+    for Point { x, y } in self.dots.iter() {
+      rows[(y - min_y) as usize][(x - min_x) as usize] = '#';
+    }
+
+    rows.into_iter().map(|row| row.into_iter().collect::<String>() + "\n").collect()
+  }
+}
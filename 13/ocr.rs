@@ -0,0 +1,57 @@
+#![allow(dead_code)]
+
+use std::collections::HashSet;
+
+const GLYPH_WIDTH: i32 = 4;
+const GLYPH_HEIGHT: i32 = 6;
+// Letters are separated by a blank column
+const GLYPH_SPACING: i32 = GLYPH_WIDTH + 1;
+
+// The letters of the 4x6 font used by the puzzles, row by row
+const FONT: [(char, [&str; 6]); 16] = [
+  ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+  ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+  ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+  ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+  ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+  ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+  ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+  ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+  ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+  ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+  ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+  ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+  ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+  ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+  ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+  ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+fn matches(glyph: &[&str; 6], dots: &HashSet<(i32, i32)>, left: i32) -> bool {
+  glyph.iter().enumerate().all(|(y, row)| {
+    row.chars().enumerate().all(|(x, c)| (c == '#') == dots.contains(&(left + x as i32, y as i32)))
+  })
+}
+
+// Reads the letters spelled by the dots, None if the dots don't line up as letters or a letter isn't known.
+// The text may start anywhere, it is moved to the origin first.
+pub fn recognise<I: IntoIterator<Item = (i32, i32)>>(dots: I) -> Option<String> {
+  let dots: Vec<(i32, i32)> = dots.into_iter().collect();
+  let min_x = dots.iter().map(|(x, _)| *x).min()?;
+  let min_y = dots.iter().map(|(_, y)| *y).min()?;
+  let dots: HashSet<(i32, i32)> = dots.into_iter().map(|(x, y)| (x - min_x, y - min_y)).collect();
+
+  let max_x = dots.iter().map(|(x, _)| *x).max()?;
+  if dots.iter().any(|(_, y)| *y >= GLYPH_HEIGHT) {
+    return None;
+  }
+
+  // Dots in the blank column between letters can't be read either
+  if dots.iter().any(|(x, _)| x % GLYPH_SPACING == GLYPH_WIDTH) {
+    return None;
+  }
+
+  (0..=max_x / GLYPH_SPACING).map(|i| {
+    FONT.iter().find(|(_, glyph)| matches(glyph, &dots, i * GLYPH_SPACING)).map(|(c, _)| *c)
+  }).collect()
+}
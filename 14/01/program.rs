@@ -2,116 +2,61 @@ use std::env;
 use std::fs::File;
 use std::io::{self, BufRead};
 use std::path::Path;
+use std::process;
 use std::collections::HashMap;
 
-struct PairIter<'a, T> {
-  arr: &'a Vec<T>,
-  idx: usize,
-}
-
-impl <'a, T> PairIter<'a, T> {
-  fn new(arr: &'a Vec<T>) -> PairIter<'a, T> {
-    PairIter { arr: arr, idx: 1 }
-  }
-}
-
-impl <'a, T> Iterator for PairIter<'a, T> where T: Copy {
-  type Item = (T, T);
+#[path = "../../common/biguint.rs"]
+mod biguint;
+#[path = "../../common/matrix.rs"]
+mod matrix;
+#[path = "../polymer.rs"]
+mod polymer;
 
-  fn next(&mut self) -> Option<(T, T)> {
-    if self.idx >= self.arr.len() {
-      None
-    } else {
-      let idx = self.idx;
-      self.idx += 1;
+use biguint::BigUint;
+use polymer::Polymer;
 
-      Some((self.arr[idx - 1], self.arr[idx]))
+fn main() {
+  let mut steps = 10_usize;
+  let mut files: Vec<String> = Vec::new();
+
+  let mut args = env::args().skip(1);
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "--steps" => steps = args.next().and_then(|v| v.parse().ok()).expect("Expected a number of steps"),
+      _ => files.push(arg),
     }
   }
-}
-
-fn main() {
-  let args: Vec<String> = env::args().collect();
 
-  if args.len() != 2 {
-    panic!("Expected exactly 1 argument, got {}", args.len());
+  if files.len() != 1 {
+    panic!("Expected exactly 1 input file, got {}", files.len());
   }
 
-  if let Ok(lines) = read_lines(&args[1]) {
-    let mut chain: Vec<char> = Vec::new();
-    let mut rules: HashMap<(char, char), char> = HashMap::new();
-
-    for line in lines {
-      let l = line.unwrap();
-
-      if l.len() == 0 {
-        continue;
-      }
-      
-      if chain.len() == 0 {
-        chain = l.chars().collect();
-      } else {
-        let parts = l.split(" -> ").collect::<Vec<_>>();
-
-        assert_eq!(parts.len(), 2);
-        assert_eq!(parts[0].len(), 2);
-        assert_eq!(parts[1].len(), 1);
+  if let Ok(lines) = read_lines(&files[0]) {
+    let polymer = Polymer::parse(lines.map(|line| line.unwrap())).unwrap_or_else(|err| {
+      eprintln!("Invalid polymer, {}", err);
+      process::exit(1);
+    });
+    let mut chain = polymer.template.clone();
 
-        let from: Vec<char> = parts[0].chars().collect();
-        let to = parts[1].chars().next().unwrap();
-
-        rules.insert((from[0], from[1]), to);
-      }
-    }
-
-    for i in 0..10 {
-      let mut next_chain: Vec<char> = Vec::new();
-
-      for (a, b) in PairIter::new(&chain) {
-        if next_chain.len() == 0 {
-          next_chain.push(a);
-        }
-
-        if let Some(extra) = rules.get(&(a, b)) {
-          next_chain.push(*extra);
-        }
-
-        next_chain.push(b);
-      }
-
-      chain = next_chain;
+    for i in 0..steps {
+      chain = polymer.step(&chain);
       println!("Line after iteration {}: {}", i + 1, chain.iter().collect::<String>());
     }
 
-    println!("");
-
-    let mut counts: HashMap<char, usize> = HashMap::new();
+    println!();
 
+    let mut counts: HashMap<char, u64> = HashMap::new();
     for c in chain {
-      if counts.contains_key(&c) {
-        *counts.get_mut(&c).unwrap() += 1;
-      } else {
-        counts.insert(c, 1);
-      }
-    }
-
-    for (c, count) in counts.iter() {
-      println!("{} => {}", c, count);
+      *counts.entry(c).or_insert(0) += 1;
     }
 
-    println!("");
+    let counts: Vec<(char, BigUint)> = counts.into_iter().map(|(c, count)| (c, BigUint::from(count))).collect();
+    polymer::print_histogram(&counts);
 
-    let mut min = usize::MAX;
-    let mut max = 0_usize;
+    println!();
 
-    for (_, count) in counts {
-      if count < min {
-        min = count;
-      }
-      if count > max {
-        max = count;
-      }
-    }
+    let min = counts.iter().map(|(_, count)| count).min().unwrap();
+    let max = counts.iter().map(|(_, count)| count).max().unwrap();
 
     println!("min: {}, max: {}, diff: {}", min, max, max - min);
   } else {
@@ -125,4 +70,4 @@ fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
 where P: AsRef<Path>, {
     let file = File::open(filename)?;
     Ok(io::BufReader::new(file).lines())
-}
\ No newline at end of file
+}
@@ -2,147 +2,90 @@ use std::env;
 use std::fs::File;
 use std::io::{self, BufRead};
 use std::path::Path;
-use std::collections::HashMap;
+use std::process;
 
-struct PairIter<'a, T> {
-  arr: &'a Vec<T>,
-  idx: usize,
-}
-
-impl <'a, T> PairIter<'a, T> {
-  fn new(arr: &'a Vec<T>) -> PairIter<'a, T> {
-    PairIter { arr: arr, idx: 1 }
-  }
-}
+#[path = "../../common/biguint.rs"]
+mod biguint;
+#[path = "../../common/matrix.rs"]
+mod matrix;
+#[path = "../polymer.rs"]
+mod polymer;
 
-impl <'a, T> Iterator for PairIter<'a, T> where T: Copy {
-  type Item = (T, T);
+use matrix::{Arithmetic, Exact, Modular};
+use polymer::{PairModel, Polymer};
 
-  fn next(&mut self) -> Option<(T, T)> {
-    if self.idx >= self.arr.len() {
-      None
-    } else {
-      let idx = self.idx;
-      self.idx += 1;
+// Stepping through one step at a time to cross-check takes too long beyond this
+const MAX_CHECK_STEPS: u64 = 100_000;
 
-      Some((self.arr[idx - 1], self.arr[idx]))
-    }
-  }
-}
-
-fn count_char(counts: &mut HashMap<char, usize>, c: &char, count: usize) -> () {
-  if counts.contains_key(c) {
-    *counts.get_mut(c).unwrap() += count;
+fn count<A: Arithmetic>(model: &PairModel, steps: u64, arith: &A, iterative: bool, check: bool) -> Vec<(char, A::Value)> {
+  let counts = if iterative {
+    model.count_iterative(steps, arith)
   } else {
-    counts.insert(*c, count);
-  }
-}
+    model.count_matrix(steps, arith)
+  };
 
-fn merge_maps(target: &mut HashMap<char, usize>, source: &HashMap<char, usize>) -> () {
-  for (k, v) in source.iter() {
-    if target.contains_key(k) {
-      *target.get_mut(k).unwrap() += *v;
-    } else {
-      target.insert(*k, *v);
+  if check {
+    if steps > MAX_CHECK_STEPS {
+      eprintln!("Can't cross-check more than {} steps", MAX_CHECK_STEPS);
+      process::exit(1);
     }
-  }
-}
 
-fn count_chars(cache: &mut HashMap<(char, char, usize), HashMap<char, usize>>, rules: &HashMap<(char, char), char>, a: char, b: char, i: usize) -> () {
-  if cache.contains_key(&(a, b, i)) {
-    return;
+    let other = if iterative { model.count_matrix(steps, arith) } else { model.count_iterative(steps, arith) };
+    assert!(counts == other, "Iterative and matrix counts disagree");
+    println!("Iterative and matrix counts agree");
   }
 
-  if i >= 40 {
-    cache.insert((a, b, i), HashMap::new());
-  } else if let Some(extra) = rules.get(&(a, b)) {
-    let mut counts: HashMap<char, usize> = HashMap::new();
-    count_char(&mut counts, extra, 1);
-
-    count_chars(cache, rules, a, *extra, i + 1);
-    count_chars(cache, rules, *extra, b, i + 1);
-
-    merge_maps(&mut counts, cache.get(&(a, *extra, i + 1)).unwrap());
-    merge_maps(&mut counts, cache.get(&(*extra, b, i + 1)).unwrap());
-
-    cache.insert((a, b, i), counts);
-  } else {
-    cache.insert((a, b, i), HashMap::new());
-  }
+  counts
 }
 
 fn main() {
-  let args: Vec<String> = env::args().collect();
-
-  if args.len() != 2 {
-    panic!("Expected exactly 1 argument, got {}", args.len());
-  }
-
-  if let Ok(lines) = read_lines(&args[1]) {
-    let mut chain: Vec<char> = Vec::new();
-    let mut rules: HashMap<(char, char), char> = HashMap::new();
-
-    for line in lines {
-      let l = line.unwrap();
-
-      if l.len() == 0 {
-        continue;
-      }
-      
-      if chain.len() == 0 {
-        chain = l.chars().collect();
-      } else {
-        let parts = l.split(" -> ").collect::<Vec<_>>();
-
-        assert_eq!(parts.len(), 2);
-        assert_eq!(parts[0].len(), 2);
-        assert_eq!(parts[1].len(), 1);
-
-        let from: Vec<char> = parts[0].chars().collect();
-        let to = parts[1].chars().next().unwrap();
-
-        rules.insert((from[0], from[1]), to);
-      }
-    }
-
-    let mut cache: HashMap<(char, char, usize), HashMap<char, usize>> = HashMap::new();
-
-    for (a, b) in PairIter::new(&chain) {
-      count_chars(&mut cache, &rules, a, b, 0);
+  let mut steps = 40_u64;
+  let mut modulus: Option<u64> = None;
+  let mut iterative = false;
+  let mut check = false;
+  let mut files: Vec<String> = Vec::new();
+
+  let mut args = env::args().skip(1);
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "--steps" => steps = args.next().and_then(|v| v.parse().ok()).expect("Expected a number of steps"),
+      "--modulus" => modulus = Some(args.next().and_then(|v| v.parse().ok()).filter(|v| *v > 0).expect("Expected a positive modulus")),
+      "--iterative" => iterative = true,
+      "--check" => check = true,
+      _ => files.push(arg),
     }
+  }
 
-    let mut counts: HashMap<char, usize> = HashMap::new();
-    for (a, b) in PairIter::new(&chain) {
-      if counts.len() == 0 {
-        counts.insert(a, 1);
-      }
+  if files.len() != 1 {
+    panic!("Expected exactly 1 input file, got {}", files.len());
+  }
 
-      if let Some(insertions) = cache.get(&(a, b, 0)) {
-        merge_maps(&mut counts, insertions);
+  if let Ok(lines) = read_lines(&files[0]) {
+    let polymer = Polymer::parse(lines.map(|line| line.unwrap())).unwrap_or_else(|err| {
+      eprintln!("Invalid polymer, {}", err);
+      process::exit(1);
+    });
+    let model = PairModel::new(&polymer).unwrap_or_else(|err| {
+      eprintln!("Can't count with matrices, {}, use 14-01 with --steps instead", err);
+      process::exit(1);
+    });
+
+    if let Some(modulus) = modulus {
+      println!("Counts modulo {}:", modulus);
+      for (c, count) in count(&model, steps, &Modular(modulus), iterative, check) {
+        println!("{} => {}", c, count);
       }
-
-      count_char(&mut counts, &b, 1);
-    }
-
-    println!("");
-
-    for (c, count) in counts.iter() {
-      println!("{} => {}", c, count);
+      return;
     }
 
-    println!("");
+    let counts: Vec<_> = count(&model, steps, &Exact, iterative, check).into_iter().filter(|(_, count)| !count.is_zero()).collect();
 
-    let mut min = usize::MAX;
-    let mut max = 0_usize;
+    println!();
+    polymer::print_histogram(&counts);
+    println!();
 
-    for (_, count) in counts {
-      if count < min {
-        min = count;
-      }
-      if count > max {
-        max = count;
-      }
-    }
+    let min = counts.iter().map(|(_, count)| count).min().unwrap();
+    let max = counts.iter().map(|(_, count)| count).max().unwrap();
 
     println!("min: {}, max: {}, diff: {}", min, max, max - min);
   } else {
@@ -156,4 +99,4 @@ fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
 where P: AsRef<Path>, {
     let file = File::open(filename)?;
     Ok(io::BufReader::new(file).lines())
-}
\ No newline at end of file
+}
@@ -0,0 +1,199 @@
+#![allow(dead_code)]
+
+use std::cmp;
+use std::collections::{BTreeSet, HashMap};
+
+use crate::biguint::BigUint;
+use crate::matrix::{Arithmetic, Matrix};
+
+pub struct Polymer {
+  pub template: Vec<char>,
+  // Elements inserted by each context. The insertion goes between the last two elements of the
+  // context, the elements before them have to precede the pair.
+  pub rules: HashMap<Vec<char>, Vec<char>>,
+}
+
+impl Polymer {
+  // Parses the template followed by rules like `CH -> B`, `CH -> BN` or `NCH -> B`. Errors mention the
+  // line they're on.
+  pub fn parse<I: Iterator<Item = String>>(lines: I) -> Result<Polymer, String> {
+    let mut template: Vec<char> = Vec::new();
+    let mut rules = HashMap::new();
+
+    for (i, line) in lines.enumerate() {
+      let line = line.trim();
+
+      if line.is_empty() {
+        continue;
+      }
+
+      if template.is_empty() {
+        template = line.chars().collect();
+        continue;
+      }
+
+      let (from, to) = line.split_once("->").ok_or_else(|| format!("line {}: expected a rule AB -> C, got {}", i + 1, line))?;
+      let from: Vec<char> = from.trim().chars().collect();
+      let to: Vec<char> = to.trim().chars().collect();
+
+      if from.len() < 2 || to.is_empty() {
+        return Err(format!("line {}: expected at least a pair and an element to insert, got {}", i + 1, line));
+      }
+
+      if rules.insert(from, to).is_some() {
+        return Err(format!("line {}: duplicate rule {}", i + 1, line));
+      }
+    }
+
+    if template.is_empty() {
+      return Err(String::from("expected a polymer template"));
+    }
+
+    Ok(Polymer { template, rules })
+  }
+
+  // Every element in the template or the rules, sorted
+  pub fn elements(&self) -> Vec<char> {
+    let mut elements: BTreeSet<char> = self.template.iter().copied().collect();
+
+    for (from, to) in self.rules.iter() {
+      elements.extend(from.iter().chain(to.iter()));
+    }
+
+    elements.into_iter().collect()
+  }
+
+  pub fn max_context(&self) -> usize {
+    self.rules.keys().map(|from| from.len()).max().unwrap_or(2)
+  }
+
+  // The insertion between `chain[idx - 1]` and `chain[idx]`, the rule with the longest context wins
+  fn insertion(&self, chain: &[char], idx: usize) -> Option<&Vec<char>> {
+    (2..=cmp::min(self.max_context(), idx + 1)).rev().find_map(|len| self.rules.get(&chain[idx + 1 - len..=idx]))
+  }
+
+  // Inserts into every gap of the chain at once
+  pub fn step(&self, chain: &[char]) -> Vec<char> {
+    let mut next = Vec::with_capacity(chain.len() * 2);
+
+    for (idx, c) in chain.iter().enumerate() {
+      if idx > 0 {
+        if let Some(insertion) = self.insertion(chain, idx) {
+          next.extend(insertion.iter());
+        }
+      }
+
+      next.push(*c);
+    }
+
+    next
+  }
+}
+
+// Prints every element's count from most to least common with its share of the polymer
+pub fn print_histogram(counts: &[(char, BigUint)]) {
+  let mut counts: Vec<&(char, BigUint)> = counts.iter().filter(|(_, count)| !count.is_zero()).collect();
+  counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+
+  let total: BigUint = counts.iter().map(|(_, count)| count.clone()).sum();
+  let max = match counts.first() {
+    Some((_, max)) => max,
+    None => return,
+  };
+  let width = counts.iter().map(|(_, count)| count.to_string().len()).max().unwrap_or(0);
+
+  for (c, count) in counts.iter() {
+    let bar = "#".repeat((count.ratio(max) * 40.0).round() as usize);
+    println!("{} {:>width$} {:>7.3}% {}", c, count.to_string(), count.ratio(&total) * 100.0, bar, width = width);
+  }
+}
+
+/// Pair insertion as a linear map
+///
+/// The polymer is tracked as the number of times each pair of elements occurs. One step sends every pair
+/// to the pairs it turns into, which is the same for every occurrence, so `steps` steps are the step
+/// matrix raised to the power `steps`. Rules with more context than a pair break this, what gets inserted
+/// then depends on more than the pair.
+pub struct PairModel {
+  elements: Vec<char>,
+  // Pairs each pair turns into after one step, by pair index
+  successors: Vec<Vec<usize>>,
+  initial: Vec<u64>,
+  // Insertions never happen at the ends, so the last element stays the last element
+  last: char,
+}
+
+impl PairModel {
+  pub fn new(polymer: &Polymer) -> Result<PairModel, String> {
+    if polymer.max_context() > 2 {
+      return Err(format!("rules with a context of {} elements can only be stepped through one at a time", polymer.max_context()));
+    }
+
+    let elements = polymer.elements();
+    let n = elements.len();
+    let index: HashMap<char, usize> = elements.iter().enumerate().map(|(i, c)| (*c, i)).collect();
+    let pair = |a: char, b: char| index[&a] * n + index[&b];
+
+    let successors = (0..n * n).map(|p| {
+      let (a, b) = (elements[p / n], elements[p % n]);
+
+      match polymer.rules.get(&vec![a, b]) {
+        Some(insertion) => {
+          let chain: Vec<char> = std::iter::once(a).chain(insertion.iter().copied()).chain(std::iter::once(b)).collect();
+          chain.windows(2).map(|window| pair(window[0], window[1])).collect()
+        },
+        None => vec![p],
+      }
+    }).collect();
+
+    let mut initial = vec![0; n * n];
+    for window in polymer.template.windows(2) {
+      initial[pair(window[0], window[1])] += 1;
+    }
+
+    Ok(PairModel { elements, successors, initial, last: *polymer.template.last().unwrap() })
+  }
+
+  // Every pair is counted by its first element, plus the last element of the polymer
+  fn element_counts<A: Arithmetic>(&self, pairs: &[A::Value], arith: &A) -> Vec<(char, A::Value)> {
+    let n = self.elements.len();
+
+    self.elements.iter().enumerate().map(|(i, c)| {
+      let start = if *c == self.last { arith.value(1) } else { arith.zero() };
+      let count = pairs[i * n..(i + 1) * n].iter().fold(start, |sum, count| arith.add(&sum, count));
+
+      (*c, count)
+    }).collect()
+  }
+
+  fn initial<A: Arithmetic>(&self, arith: &A) -> Vec<A::Value> {
+    self.initial.iter().map(|count| arith.value(*count)).collect()
+  }
+
+  // Counts the elements after `steps` steps, one step at a time
+  pub fn count_iterative<A: Arithmetic>(&self, steps: u64, arith: &A) -> Vec<(char, A::Value)> {
+    let mut pairs = self.initial(arith);
+
+    for _ in 0..steps {
+      let mut next = vec![arith.zero(); pairs.len()];
+
+      for (p, count) in pairs.iter().enumerate().filter(|(_, count)| !arith.is_zero(count)) {
+        for q in self.successors[p].iter() {
+          next[*q] = arith.add(&next[*q], count);
+        }
+      }
+
+      pairs = next;
+    }
+
+    self.element_counts(&pairs, arith)
+  }
+
+  // Counts the elements after `steps` steps in O(log steps) matrix products
+  pub fn count_matrix<A: Arithmetic>(&self, steps: u64, arith: &A) -> Vec<(char, A::Value)> {
+    let step = Matrix::transitions(&self.successors, arith);
+
+    let pairs = step.pow(steps, arith).apply(&self.initial(arith), arith);
+    self.element_counts(&pairs, arith)
+  }
+}
@@ -0,0 +1,174 @@
+#![allow(dead_code)]
+
+use std::io::{self, BufRead, Read};
+
+#[derive(Debug)]
+pub enum ReadError {
+  Truncated { needed: u32, available: u32 },
+  Io(io::Error),
+}
+
+impl From<io::Error> for ReadError {
+  fn from(err: io::Error) -> ReadError {
+    ReadError::Io(err)
+  }
+}
+
+pub struct BitReader<R: Read> {
+  inner: R,
+  byte: u8,
+  bits_left: u32,
+  position: usize,
+  // Number of low bits of the last byte read that are padding rather than data
+  padding: fn(&R) -> u32,
+}
+
+impl<R: Read> BitReader<R> {
+  pub fn new(inner: R) -> BitReader<R> {
+    BitReader::with_padding(inner, |_| 0)
+  }
+
+  // For readers whose last byte may only be partly filled, `padding` is asked after every byte read
+  pub fn with_padding(inner: R, padding: fn(&R) -> u32) -> BitReader<R> {
+    BitReader { inner, byte: 0, bits_left: 0, position: 0, padding }
+  }
+
+  pub fn position(&self) -> usize {
+    self.position
+  }
+
+  // Returns false if the underlying reader is exhausted.
+  fn fill(&mut self) -> io::Result<bool> {
+    if self.bits_left > 0 {
+      return Ok(true);
+    }
+
+    let mut buf = [0_u8; 1];
+    loop {
+      match self.inner.read(&mut buf) {
+        Ok(0) => return Ok(false),
+        Ok(_) => {
+          let padding = (self.padding)(&self.inner);
+          self.byte = buf[0] >> padding;
+          self.bits_left = 8 - padding;
+          return Ok(true);
+        },
+        Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+        Err(err) => return Err(err),
+      }
+    }
+  }
+
+  pub fn is_eof(&mut self) -> io::Result<bool> {
+    self.fill().map(|filled| !filled)
+  }
+
+  // Reads `n` bits, most significant first.
+  pub fn read_bits(&mut self, n: u32) -> Result<u32, ReadError> {
+    assert!(n <= 32);
+
+    let mut result = 0_u64;
+    let mut remaining = n;
+
+    while remaining > 0 {
+      if !self.fill()? {
+        return Err(ReadError::Truncated { needed: n, available: n - remaining });
+      }
+
+      let take = remaining.min(self.bits_left);
+      let shift = self.bits_left - take;
+      let chunk = (self.byte as u32 >> shift) & ((1 << take) - 1);
+
+      result = (result << take) | chunk as u64;
+      self.bits_left -= take;
+      remaining -= take;
+      self.position += take as usize;
+    }
+
+    Ok(result as u32)
+  }
+}
+
+// Decodes a single line of hexadecimal text into bytes, signalling end of input at the first newline. An
+// odd final digit becomes the high half of the last byte, the low half is padding.
+pub struct HexReader<'a, R: BufRead> {
+  inner: &'a mut R,
+  offset: usize,
+  done: bool,
+  padded: bool,
+}
+
+impl<'a, R: BufRead> HexReader<'a, R> {
+  pub fn new(inner: &'a mut R) -> HexReader<'a, R> {
+    HexReader { inner, offset: 0, done: false, padded: false }
+  }
+
+  // Reads the bits of the digits, leaving out the padding after an odd final digit
+  pub fn into_bits(self) -> BitReader<HexReader<'a, R>> {
+    BitReader::with_padding(self, HexReader::padding)
+  }
+
+  fn padding(&self) -> u32 {
+    if self.padded { 4 } else { 0 }
+  }
+
+  fn next_digit(&mut self) -> io::Result<Option<u8>> {
+    if self.done {
+      return Ok(None);
+    }
+
+    let c = match self.inner.fill_buf()?.first() {
+      None => {
+        self.done = true;
+        return Ok(None);
+      },
+      Some(c) => *c as char,
+    };
+    self.inner.consume(1);
+
+    if c == '\n' {
+      self.done = true;
+      return Ok(None);
+    }
+
+    if c == '\r' {
+      return self.next_digit();
+    }
+
+    match c.to_digit(16) {
+      Some(digit) => {
+        self.offset += 1;
+        Ok(Some(digit as u8))
+      },
+      None => Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid hexadecimal digit {:?} at character {}", c, self.offset),
+      )),
+    }
+  }
+}
+
+impl<'a, R: BufRead> Read for HexReader<'a, R> {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    let mut written = 0;
+
+    while written < buf.len() {
+      let high = match self.next_digit()? {
+        Some(digit) => digit,
+        None => break,
+      };
+      let low = match self.next_digit()? {
+        Some(digit) => digit,
+        None => {
+          self.padded = true;
+          0
+        },
+      };
+
+      buf[written] = (high << 4) | low;
+      written += 1;
+    }
+
+    Ok(written)
+  }
+}
@@ -0,0 +1,158 @@
+#![allow(dead_code)]
+
+use std::fmt;
+
+use std::io::Read;
+
+use crate::biguint::BigUint;
+use crate::bit_reader::{BitReader, ReadError};
+
+pub enum Package {
+  Literal(u8, u8, BigUint),
+  Operator(u8, u8, Vec<Package>),
+}
+
+#[derive(Debug,PartialEq,Eq)]
+pub enum ErrorKind {
+  Io(String),
+  Truncated { needed: u32, available: u32 },
+  LengthMismatch { expected_end: usize },
+  InvalidOperandCount { type_id: u8, count: usize },
+  NonZeroPadding,
+}
+
+#[derive(Debug,PartialEq,Eq)]
+pub struct ParseError {
+  pub offset: usize,
+  pub kind: ErrorKind,
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "at bit {}: ", self.offset)?;
+
+    match &self.kind {
+      ErrorKind::Io(message) => write!(f, "{}", message),
+      ErrorKind::Truncated { needed, available } => write!(f, "expected {} more bits, only {} left", needed, available),
+      ErrorKind::LengthMismatch { expected_end } => write!(f, "subpackets overrun their declared end at bit {}", expected_end),
+      ErrorKind::InvalidOperandCount { type_id, count } => write!(f, "operator {} can't take {} subpackets", type_id, count),
+      ErrorKind::NonZeroPadding => write!(f, "expected trailing zeros, got a 1"),
+    }
+  }
+}
+
+fn error<T>(offset: usize, kind: ErrorKind) -> Result<T, ParseError> {
+  Err(ParseError { offset, kind })
+}
+
+fn read_n<R: Read>(bits: &mut BitReader<R>, len: u32) -> Result<u32, ParseError> {
+  let offset = bits.position();
+
+  bits.read_bits(len).or_else(|err| match err {
+    ReadError::Truncated { needed, available } => error(offset, ErrorKind::Truncated { needed, available }),
+    ReadError::Io(err) => error(offset, ErrorKind::Io(err.to_string())),
+  })
+}
+
+fn read_single<R: Read>(bits: &mut BitReader<R>) -> Result<u8, ParseError> {
+  read_n(bits, 1).map(|value| value as u8)
+}
+
+fn read_triple<R: Read>(bits: &mut BitReader<R>) -> Result<u8, ParseError> {
+  read_n(bits, 3).map(|value| value as u8)
+}
+
+fn check_operand_count(type_id: u8, count: usize, offset: usize) -> Result<(), ParseError> {
+  let valid = match type_id {
+    5..=7 => count == 2,
+    _ => count >= 1,
+  };
+
+  if valid {
+    Ok(())
+  } else {
+    error(offset, ErrorKind::InvalidOperandCount { type_id, count })
+  }
+}
+
+// Folds a package while reading it: `literal` gets the version and value of each literal package and `operator`
+// the version, type and folded subpackages of each operator package. Only the folded subpackages of the
+// operators still being read are kept, never the whole package tree.
+pub fn fold_package<R: Read, T, L, O>(bits: &mut BitReader<R>, literal: &mut L, operator: &mut O) -> Result<T, ParseError>
+where L: FnMut(u8, BigUint) -> T, O: FnMut(u8, u8, Vec<T>) -> T {
+  let start = bits.position();
+  let version = read_triple(bits)?;
+  let type_identifier = read_triple(bits)?;
+
+  match type_identifier {
+    4_u8 => {
+      let mut value = BigUint::zero();
+
+      loop {
+        let should_continue = read_single(bits)? == 1_u8;
+
+        value.push_bits(4, read_n(bits, 4)?);
+
+        if !should_continue {
+          break;
+        }
+      }
+
+      Ok(literal(version, value))
+    },
+    _ => {
+      let length_type = read_single(bits)?;
+      let mut subpackages: Vec<T> = Vec::new();
+
+      if length_type == 1_u8 {
+        let length = read_n(bits, 11)?;
+
+        for _ in 0..length {
+          subpackages.push(fold_package(bits, literal, operator)?);
+        }
+      } else {
+        let length = read_n(bits, 15)? as usize;
+        let end_idx = bits.position() + length;
+
+        while bits.position() < end_idx {
+          subpackages.push(fold_package(bits, literal, operator)?);
+        }
+
+        if bits.position() != end_idx {
+          return error(bits.position(), ErrorKind::LengthMismatch { expected_end: end_idx });
+        }
+      }
+
+      check_operand_count(type_identifier, subpackages.len(), start)?;
+
+      Ok(operator(version, type_identifier, subpackages))
+    }
+  }
+}
+
+pub fn parse_package<R: Read>(bits: &mut BitReader<R>) -> Result<Package, ParseError> {
+  fold_package(bits, &mut |version, value| Package::Literal(version, 4, value), &mut Package::Operator)
+}
+
+// Folds the outermost package and checks that only zeros follow it
+pub fn fold_transmission<R: Read, T, L, O>(bits: &mut BitReader<R>, literal: &mut L, operator: &mut O) -> Result<T, ParseError>
+where L: FnMut(u8, BigUint) -> T, O: FnMut(u8, u8, Vec<T>) -> T {
+  let folded = fold_package(bits, literal, operator)?;
+
+  loop {
+    let offset = bits.position();
+    match bits.is_eof() {
+      Ok(true) => return Ok(folded),
+      Ok(false) => {},
+      Err(err) => return error(offset, ErrorKind::Io(err.to_string())),
+    }
+
+    if read_single(bits)? != 0 {
+      return error(offset, ErrorKind::NonZeroPadding);
+    }
+  }
+}
+
+pub fn parse_transmission<R: Read>(bits: &mut BitReader<R>) -> Result<Package, ParseError> {
+  fold_transmission(bits, &mut |version, value| Package::Literal(version, 4, value), &mut Package::Operator)
+}
@@ -2,74 +2,55 @@ use std::env;
 use std::fs::File;
 use std::io::{self, BufRead};
 use std::path::Path;
+use std::process;
 
-struct TargetArea {
-  x_min: u32,
-  x_max: u32,
+#[path = "../plot.rs"]
+mod plot;
+#[path = "../trick_shot.rs"]
+mod trick_shot;
 
-  y_min: i32,
-  y_max: i32,
-}
-
-fn parse_target_area(line: String) -> TargetArea {
-  let prefix = "target area: ";
-  if !line.starts_with(prefix) {
-    panic!("expected target area");
-  }
-
-  let parts = line[prefix.len()..].split(", ");
-
-  let mut x: Option<(u32, u32)> = None;
-  let mut y: Option<(i32, i32)> = None;
-
-  for part in parts {
-    let subparts = part[2..].split("..").collect::<Vec<_>>();
-
-    if part.starts_with("x=") {
-      assert_eq!(subparts.len(), 2);
-
-      x = Some((subparts[0].parse::<u32>().unwrap(), subparts[1].parse::<u32>().unwrap()));
-    } else if part.starts_with("y=") {
-      assert_eq!(subparts.len(), 2);
-      
-      y = Some((subparts[0].parse::<i32>().unwrap(), subparts[1].parse::<i32>().unwrap()));
-    } else {
-      panic!("invalid part {}", part);
-    }
-  }
+use trick_shot::TargetArea;
 
-  if let Some((x_min, x_max)) = x {
-    if let Some((y_min, y_max)) = y {
-      return TargetArea {
-        x_min: x_min,
-        x_max: x_max,
-        y_min: y_min,
-        y_max: y_max,
-      };
+fn main() {
+  let mut plots: Vec<(i64, i64)> = Vec::new();
+  let mut svg: Option<String> = None;
+  let mut files: Vec<String> = Vec::new();
+
+  let mut args = env::args().skip(1);
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "--plot" => plots.push(args.next().and_then(|v| plot::parse_velocity(&v)).expect("Expected a velocity vx,vy")),
+      "--svg" => svg = Some(args.next().expect("Expected a path for the SVG")),
+      _ => files.push(arg),
     }
   }
 
-  panic!("Expected x and y to be given");
-}
-
-fn main() {
-  let args: Vec<String> = env::args().collect();
-
-  if args.len() != 2 {
-    panic!("Expected exactly 1 argument, got {}", args.len());
+  if files.len() != 1 {
+    panic!("Expected exactly 1 input file, got {}", files.len());
   }
 
-  if let Ok(lines) = read_lines(&args[1]) {
+  if let Ok(lines) = read_lines(&files[0]) {
     for line in lines {
-      let area = parse_target_area(line.unwrap());
-      
-      // The ideal scenario always has a X velocity that ends up on zero inside
-      // the target area, which means we don't actually need to consider X for
-      // this excercise.
-
-      let max_y_velocity = area.y_min * -1 - 1;
-
-      println!("{}", max_y_velocity * (max_y_velocity + 1) / 2);
+      let area = TargetArea::parse(&line.unwrap()).unwrap_or_else(|err| {
+        eprintln!("Invalid target area, {}", err);
+        process::exit(1);
+      });
+
+      match trick_shot::solve(&area) {
+        Ok(solution) => println!("{}", solution.highest_apex),
+        Err(err) => {
+          eprintln!("Can't find the highest shot, {}", err);
+          process::exit(1);
+        },
+      }
+
+      if !plots.is_empty() {
+        println!();
+        if let Err(err) = plot::show(&area, &plots, svg.as_deref()) {
+          eprintln!("Failed to plot, {}", err);
+          process::exit(1);
+        }
+      }
     }
   } else {
     panic!("Failed to read file");
@@ -82,4 +63,4 @@ fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
 where P: AsRef<Path>, {
     let file = File::open(filename)?;
     Ok(io::BufReader::new(file).lines())
-}
\ No newline at end of file
+}
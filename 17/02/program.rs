@@ -2,114 +2,84 @@ use std::env;
 use std::fs::File;
 use std::io::{self, BufRead};
 use std::path::Path;
+use std::process;
 
-struct TargetArea {
-  x_min: u32,
-  x_max: u32,
+#[path = "../plot.rs"]
+mod plot;
+#[path = "../trick_shot.rs"]
+mod trick_shot;
 
-  y_min: i32,
-  y_max: i32,
-}
-
-fn parse_target_area(line: String) -> TargetArea {
-  let prefix = "target area: ";
-  if !line.starts_with(prefix) {
-    panic!("expected target area");
-  }
+use trick_shot::TargetArea;
 
-  let parts = line[prefix.len()..].split(", ");
+// Simulates every velocity in a box around the area and compares with the analytic solution
+fn check(area: &TargetArea, velocities: &[(i64, i64)]) {
+  let reach = [area.x_min, area.x_max, area.y_min, area.y_max].iter().map(|v| (*v as i64).abs()).max().unwrap() + 10;
 
-  let mut x: Option<(u32, u32)> = None;
-  let mut y: Option<(i32, i32)> = None;
+  let simulated: Vec<(i64, i64)> = (-reach..=reach)
+    .flat_map(|vx| (-reach..=reach).map(move |vy| (vx, vy)))
+    .filter(|velocity| trick_shot::simulate(area, *velocity))
+    .collect();
 
-  for part in parts {
-    let subparts = part[2..].split("..").collect::<Vec<_>>();
+  if simulated != velocities {
+    let missing: Vec<_> = simulated.iter().filter(|v| !velocities.contains(v)).collect();
+    let extra: Vec<_> = velocities.iter().filter(|v| !simulated.contains(v)).collect();
+    eprintln!("Simulation disagrees, missing {:?}, extra {:?}", missing, extra);
+    process::exit(1);
+  }
 
-    if part.starts_with("x=") {
-      assert_eq!(subparts.len(), 2);
+  println!("Simulation agrees on {} velocities", simulated.len());
+}
 
-      x = Some((subparts[0].parse::<u32>().unwrap(), subparts[1].parse::<u32>().unwrap()));
-    } else if part.starts_with("y=") {
-      assert_eq!(subparts.len(), 2);
-      
-      y = Some((subparts[0].parse::<i32>().unwrap(), subparts[1].parse::<i32>().unwrap()));
-    } else {
-      panic!("invalid part {}", part);
+fn main() {
+  let mut run_check = false;
+  let mut plots: Vec<(i64, i64)> = Vec::new();
+  let mut svg: Option<String> = None;
+  let mut files: Vec<String> = Vec::new();
+
+  let mut args = env::args().skip(1);
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "--check" => run_check = true,
+      "--plot" => plots.push(args.next().and_then(|v| plot::parse_velocity(&v)).expect("Expected a velocity vx,vy")),
+      "--svg" => svg = Some(args.next().expect("Expected a path for the SVG")),
+      _ => files.push(arg),
     }
   }
 
-  if let Some((x_min, x_max)) = x {
-    if let Some((y_min, y_max)) = y {
-      return TargetArea {
-        x_min: x_min,
-        x_max: x_max,
-        y_min: y_min,
-        y_max: y_max,
-      };
-    }
+  if files.len() != 1 {
+    panic!("Expected exactly 1 input file, got {}", files.len());
   }
 
-  panic!("Expected x and y to be given");
-}
+  if let Ok(lines) = read_lines(&files[0]) {
+    for line in lines {
+      let area = TargetArea::parse(&line.unwrap()).unwrap_or_else(|err| {
+        eprintln!("Invalid target area, {}", err);
+        process::exit(1);
+      });
+
+      let solution = trick_shot::solve(&area).unwrap_or_else(|err| {
+        eprintln!("Can't list the velocities, {}", err);
+        process::exit(1);
+      });
+
+      for (vx, vy) in solution.velocities.iter() {
+        println!("{}, {}", vx, vy);
+      }
 
-fn main() {
-  let args: Vec<String> = env::args().collect();
+      println!("\n{}", solution.velocities.len());
+      println!("Highest apex: {}", solution.highest_apex);
 
-  if args.len() != 2 {
-    panic!("Expected exactly 1 argument, got {}", args.len());
-  }
+      if run_check {
+        check(&area, &solution.velocities);
+      }
 
-  if let Ok(lines) = read_lines(&args[1]) {
-    for line in lines {
-      let area = parse_target_area(line.unwrap());
-
-      // the target area lies in the quadrant of x > 0 && y < 0
-      assert!(area.x_min > 0);
-      assert!(area.y_max < 0);
-      
-      // The ideal scenario always has a X velocity that ends up on zero inside
-      // the target area, which means we don't actually need to consider X for
-      // this excercise.
-
-      // max velocity is where we end up at max value after 1 tick
-      let max_x_velocity = area.x_max;
-      // min velocity is the velocity which ends up on min_x after infinite ticks.
-      // Computing n for which `min_x = n * (n+1) / 2` is hard to compute, pick a
-      // lower bound that is definitey lower: min_x = (n + 1) ** 2 / 2
-      let min_x_velocity = (area.x_min as f32 * 2_f32).sqrt().floor() as u32 - 1;
-
-      // min velocity is the velocity which ends up at min value after 1 tick
-      let min_y_velocity = area.y_min;
-      // max velocity is the velocity that ends up going so high that when it
-      // comes back down the point will land at min value the tick after hitting 0
-      let max_y_velocity = -area.y_min - 1;
-
-      let mut count = 0_usize;
-
-      for start_x_velocity in min_x_velocity..(max_x_velocity + 1) {
-        for start_y_velocity in min_y_velocity..(max_y_velocity + 1) {
-          let (mut x, mut y) = (0_u32, 0_i32);
-          let (mut x_velocity, mut y_velocity) = (start_x_velocity, start_y_velocity);
-
-          while x <= area.x_max && y >= area.y_min {
-            if x >= area.x_min && y <= area.y_max {
-              println!("{}, {}", start_x_velocity, start_y_velocity);
-              count += 1;
-              break;
-            }
-
-            if x_velocity > 0 {
-              x += x_velocity;
-              x_velocity -= 1;
-            }
-            
-            y += y_velocity;
-            y_velocity -= 1;
-          }
+      if !plots.is_empty() {
+        println!();
+        if let Err(err) = plot::show(&area, &plots, svg.as_deref()) {
+          eprintln!("Failed to plot, {}", err);
+          process::exit(1);
         }
       }
-
-      println!("\n{}", count);
     }
   } else {
     panic!("Failed to read file");
@@ -122,4 +92,4 @@ fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
 where P: AsRef<Path>, {
     let file = File::open(filename)?;
     Ok(io::BufReader::new(file).lines())
-}
\ No newline at end of file
+}
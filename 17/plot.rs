@@ -0,0 +1,130 @@
+#![allow(dead_code)]
+
+use std::cmp;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use crate::trick_shot::{self, TargetArea};
+
+// Refuse to draw ASCII plots larger than this many characters, the SVG has no such limit
+const MAX_ASCII_CELLS: i64 = 1 << 22;
+
+pub struct Plot {
+  pub area: TargetArea,
+  pub velocity: (i64, i64),
+  pub positions: Vec<(i64, i64)>,
+  pub hit: bool,
+}
+
+impl Plot {
+  pub fn new(area: &TargetArea, velocity: (i64, i64)) -> Plot {
+    let (positions, hit) = trick_shot::trajectory(area, velocity);
+
+    Plot { area: *area, velocity, positions, hit }
+  }
+
+  // Corners holding the start, the path and the target area, as min and max inclusive
+  fn bounds(&self) -> ((i64, i64), (i64, i64)) {
+    let mut min = (cmp::min(0, self.area.x_min as i64), cmp::min(0, self.area.y_min as i64));
+    let mut max = (cmp::max(0, self.area.x_max as i64), cmp::max(0, self.area.y_max as i64));
+
+    for (x, y) in self.positions.iter() {
+      min = (cmp::min(min.0, *x), cmp::min(min.1, *y));
+      max = (cmp::max(max.0, *x), cmp::max(max.1, *y));
+    }
+
+    (min, max)
+  }
+
+  pub fn summary(&self) -> String {
+    let apex = self.positions.iter().map(|(_, y)| *y).max().unwrap_or(0).max(0);
+    let result = if self.hit { "hits" } else { "misses" };
+
+    format!("{},{} {} the target after {} steps, reaching y={}", self.velocity.0, self.velocity.1, result, self.positions.len(), apex)
+  }
+}
+
+// Draws the plot like the puzzle text, `S` is the start, `#` the probe and `T` the target area
+pub fn write_ascii<W: Write>(out: &mut W, plot: &Plot) -> io::Result<()> {
+  let (min, max) = plot.bounds();
+  if (max.0 - min.0 + 1) * (max.1 - min.1 + 1) > MAX_ASCII_CELLS {
+    return Err(io::Error::new(io::ErrorKind::InvalidInput, "trajectory is too large to draw as text, write an SVG instead"));
+  }
+
+  let positions: HashSet<(i64, i64)> = plot.positions.iter().copied().collect();
+
+  for y in (min.1..=max.1).rev() {
+    let row: String = (min.0..=max.0).map(|x| {
+      if (x, y) == (0, 0) {
+        'S'
+      } else if positions.contains(&(x, y)) {
+        '#'
+      } else if plot.area.contains(x, y) {
+        'T'
+      } else {
+        '.'
+      }
+    }).collect();
+    writeln!(out, "{}", row)?;
+  }
+
+  Ok(())
+}
+
+// One SVG unit per position, flipped so y points up
+pub fn write_svg<W: Write>(out: &mut W, plot: &Plot) -> io::Result<()> {
+  let (min, max) = plot.bounds();
+  let (width, height) = (max.0 - min.0 + 2, max.1 - min.1 + 2);
+  let area = &plot.area;
+
+  writeln!(out, r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#, min.0 - 1, -max.1 - 1, width, height)?;
+  writeln!(out, "  <title>{}</title>", plot.summary())?;
+  writeln!(out, r#"  <g transform="scale(1,-1)">"#)?;
+  writeln!(out, r##"    <rect x="{}" y="{}" width="{}" height="{}" fill="#9cf" />"##,
+    area.x_min as f64 - 0.5, area.y_min as f64 - 0.5, area.x_max - area.x_min + 1, area.y_max - area.y_min + 1)?;
+
+  let points: Vec<String> = std::iter::once((0, 0)).chain(plot.positions.iter().copied()).map(|(x, y)| format!("{},{}", x, y)).collect();
+  let colour = if plot.hit { "#393" } else { "#c33" };
+  writeln!(out, r#"    <polyline points="{}" fill="none" stroke="{}" stroke-width="0.2" />"#, points.join(" "), colour)?;
+
+  for (x, y) in plot.positions.iter() {
+    writeln!(out, r#"    <circle cx="{}" cy="{}" r="0.4" fill="{}" />"#, x, y, colour)?;
+  }
+  writeln!(out, r#"    <circle cx="0" cy="0" r="0.5" fill="black" />"#)?;
+
+  writeln!(out, "  </g>")?;
+  writeln!(out, "</svg>")
+}
+
+pub fn export_svg(path: &str, plot: &Plot) -> io::Result<()> {
+  let mut out = BufWriter::new(File::create(path)?);
+  write_svg(&mut out, plot)?;
+  out.flush()
+}
+
+// Prints a summary of each trajectory followed by its ASCII plot, or writes the single trajectory to an SVG
+pub fn show(area: &TargetArea, velocities: &[(i64, i64)], svg: Option<&str>) -> io::Result<()> {
+  if svg.is_some() && velocities.len() != 1 {
+    return Err(io::Error::new(io::ErrorKind::InvalidInput, "an SVG holds exactly one trajectory"));
+  }
+
+  for velocity in velocities.iter() {
+    let plot = Plot::new(area, *velocity);
+    println!("{}", plot.summary());
+
+    match svg {
+      Some(path) => export_svg(path, &plot)?,
+      None => write_ascii(&mut io::stdout().lock(), &plot)?,
+    }
+  }
+
+  Ok(())
+}
+
+// Parses a velocity like `7,2`
+pub fn parse_velocity(str: &str) -> Option<(i64, i64)> {
+  let (x, y) = str.split_once(',')?;
+
+  Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+}
@@ -0,0 +1,250 @@
+#![allow(dead_code)]
+
+use std::cmp;
+
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub struct TargetArea {
+  pub x_min: i32,
+  pub x_max: i32,
+
+  pub y_min: i32,
+  pub y_max: i32,
+}
+
+fn parse_range(part: &str) -> Option<(i32, i32)> {
+  let (min, max) = part.split_once("..")?;
+  let (min, max) = (min.trim().parse::<i32>().ok()?, max.trim().parse::<i32>().ok()?);
+
+  Some((cmp::min(min, max), cmp::max(min, max)))
+}
+
+impl TargetArea {
+  // Parses `target area: x=20..30, y=-10..-5`
+  pub fn parse(line: &str) -> Result<TargetArea, String> {
+    let ranges = line.trim().strip_prefix("target area: ").ok_or_else(|| format!("expected target area, got {}", line))?;
+
+    let mut x: Option<(i32, i32)> = None;
+    let mut y: Option<(i32, i32)> = None;
+
+    for part in ranges.split(", ") {
+      if let Some(range) = part.strip_prefix("x=") {
+        x = Some(parse_range(range).ok_or_else(|| format!("invalid range {}", part))?);
+      } else if let Some(range) = part.strip_prefix("y=") {
+        y = Some(parse_range(range).ok_or_else(|| format!("invalid range {}", part))?);
+      } else {
+        return Err(format!("invalid part {}", part));
+      }
+    }
+
+    match (x, y) {
+      (Some((x_min, x_max)), Some((y_min, y_max))) => Ok(TargetArea { x_min, x_max, y_min, y_max }),
+      _ => Err(String::from("expected x and y to be given")),
+    }
+  }
+
+  pub fn contains(&self, x: i64, y: i64) -> bool {
+    x >= self.x_min as i64 && x <= self.x_max as i64 && y >= self.y_min as i64 && y <= self.y_max as i64
+  }
+}
+
+// Steps `from` up to and including `to`, forever if `to` is None
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub struct StepRange {
+  pub from: i64,
+  pub to: Option<i64>,
+}
+
+impl StepRange {
+  fn intersects(&self, other: &StepRange) -> bool {
+    let from = cmp::max(self.from, other.from);
+
+    match (self.to, other.to) {
+      (Some(a), Some(b)) => from <= cmp::min(a, b),
+      (Some(to), None) | (None, Some(to)) => from <= to,
+      (None, None) => true,
+    }
+  }
+}
+
+// Position after `n` steps when starting at 0 with `velocity` and slowing down by 1 each step, ignoring the
+// drag stopping at 0
+fn launched(velocity: i64, n: i64) -> i64 {
+  n * velocity - n * (n - 1) / 2
+}
+
+fn isqrt(value: i64) -> i64 {
+  if value <= 0 {
+    return 0;
+  }
+
+  let mut root = (value as f64).sqrt() as i64;
+  while root * root > value {
+    root -= 1;
+  }
+  while (root + 1) * (root + 1) <= value {
+    root += 1;
+  }
+
+  root
+}
+
+// Steps n >= 1 where `launched(velocity, n) >= threshold`. The position is a downward parabola in n, so
+// these steps form a single range.
+fn steps_at_least(velocity: i64, threshold: i64) -> Option<(i64, i64)> {
+  // n^2 - (2v + 1) n + 2t <= 0
+  let b = 2 * velocity + 1;
+  let discriminant = b * b - 8 * threshold;
+  if discriminant < 0 {
+    return None;
+  }
+
+  let root = isqrt(discriminant);
+  let mut from = cmp::max(1, (b - root) / 2 - 1);
+  let mut to = (b + root) / 2 + 1;
+
+  // The integer square root is off by at most one step on either side
+  while from <= to && launched(velocity, from) < threshold {
+    from += 1;
+  }
+  while to >= from && launched(velocity, to) < threshold {
+    to -= 1;
+  }
+
+  if from <= to {
+    Some((from, to))
+  } else {
+    None
+  }
+}
+
+// Steps at which a probe launched with vertical velocity `velocity` is within y_min..=y_max
+pub fn y_steps(velocity: i64, y_min: i64, y_max: i64) -> Vec<StepRange> {
+  let (from, to) = match steps_at_least(velocity, y_min) {
+    Some(range) => range,
+    None => return Vec::new(),
+  };
+
+  // Cut out the steps where the probe is above the area, on the way up and on the way down
+  match steps_at_least(velocity, y_max + 1) {
+    None => vec![StepRange { from, to: Some(to) }],
+    Some((above_from, above_to)) => {
+      let mut ranges = Vec::new();
+      if from < above_from {
+        ranges.push(StepRange { from, to: Some(above_from - 1) });
+      }
+      if above_to < to {
+        ranges.push(StepRange { from: above_to + 1, to: Some(to) });
+      }
+      ranges
+    },
+  }
+}
+
+// Steps at which a probe launched with horizontal velocity `velocity` is within x_min..=x_max. Drag stops
+// the probe after |velocity| steps, after which it stays put.
+pub fn x_steps(velocity: i64, x_min: i64, x_max: i64) -> Option<StepRange> {
+  // Mirror negative velocities so the probe moves right
+  let (speed, lo, hi) = if velocity < 0 { (-velocity, -x_max, -x_min) } else { (velocity, x_min, x_max) };
+  let stop = launched(speed, speed);
+
+  if speed == 0 {
+    return if lo <= 0 && 0 <= hi { Some(StepRange { from: 1, to: None }) } else { None };
+  }
+  if stop < lo {
+    return None;
+  }
+
+  // The position only grows until the probe stops, and it reaches `lo` before then
+  let (from, _) = steps_at_least(speed, lo)?;
+
+  if stop <= hi {
+    return Some(StepRange { from, to: None });
+  }
+
+  let (past_from, _) = steps_at_least(speed, hi + 1)?;
+  if past_from <= from {
+    None
+  } else {
+    Some(StepRange { from, to: Some(past_from - 1) })
+  }
+}
+
+pub struct Solution {
+  // Every launch velocity that is within the target area after some step, ordered by x then y
+  pub velocities: Vec<(i64, i64)>,
+  pub highest_apex: i64,
+}
+
+// Finds every velocity hitting `area` without simulating the probes. Fails when infinitely many velocities
+// hit, which happens when the probe can stop above or inside an area containing y = 0: it comes back through
+// y = 0 from any height.
+pub fn solve(area: &TargetArea) -> Result<Solution, String> {
+  let (x_min, x_max) = (area.x_min as i64, area.x_max as i64);
+  let (y_min, y_max) = (area.y_min as i64, area.y_max as i64);
+
+  // Any step of a probe moving right takes it at least its starting velocity away, same to the left
+  let x_ranges: Vec<(i64, StepRange)> = (cmp::min(x_min, 0)..=cmp::max(x_max, 0))
+    .filter_map(|vx| x_steps(vx, x_min, x_max).map(|steps| (vx, steps)))
+    .collect();
+
+  let stops_in_area = x_ranges.iter().any(|(_, steps)| steps.to.is_none());
+  if stops_in_area && y_min <= 0 && 0 <= y_max {
+    return Err(String::from("infinitely many velocities hit the target area, it contains y = 0 and a point where the probe stops"));
+  }
+
+  // Going up, every point above 0 is at least the velocity high. Coming down, a probe crosses y = 0 after
+  // 2v + 1 steps and moves v + 1 down in the next step. That crossing can only count while x is in range.
+  let last_x_step = x_ranges.iter().filter_map(|(_, steps)| steps.to).max().unwrap_or(0);
+  let vy_min = cmp::min(y_min, 0);
+  let vy_max = cmp::max(cmp::max(y_max, -y_min - 1), cmp::max(last_x_step / 2, 0));
+
+  let y_ranges: Vec<(i64, Vec<StepRange>)> = (vy_min..=vy_max)
+    .map(|vy| (vy, y_steps(vy, y_min, y_max)))
+    .filter(|(_, steps)| !steps.is_empty())
+    .collect();
+
+  let mut velocities = Vec::new();
+  for (vx, x_steps) in x_ranges.iter() {
+    for (vy, y_steps) in y_ranges.iter() {
+      if y_steps.iter().any(|steps| steps.intersects(x_steps)) {
+        velocities.push((*vx, *vy));
+      }
+    }
+  }
+
+  let highest_apex = velocities.iter().map(|(_, vy)| if *vy > 0 { vy * (vy + 1) / 2 } else { 0 }).max().unwrap_or(0);
+
+  Ok(Solution { velocities, highest_apex })
+}
+
+// Runs the probe step by step, stopping once it hits the area or can't reach it anymore. Returns every
+// position after launch and whether the last one is in the area.
+pub fn trajectory(area: &TargetArea, velocity: (i64, i64)) -> (Vec<(i64, i64)>, bool) {
+  let (mut x, mut y) = (0_i64, 0_i64);
+  let (mut vx, mut vy) = velocity;
+  let (x_min, x_max) = (area.x_min as i64, area.x_max as i64);
+  let mut positions = Vec::new();
+
+  loop {
+    x += vx;
+    y += vy;
+    vx -= vx.signum();
+    vy -= 1;
+    positions.push((x, y));
+
+    if area.contains(x, y) {
+      return (positions, true);
+    }
+
+    let falling_below = vy < 0 && y < area.y_min as i64;
+    let passed = (vx >= 0 && x > x_max) || (vx <= 0 && x < x_min);
+
+    if falling_below || passed {
+      return (positions, false);
+    }
+  }
+}
+
+pub fn simulate(area: &TargetArea, velocity: (i64, i64)) -> bool {
+  trajectory(area, velocity).1
+}
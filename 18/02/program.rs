@@ -2,233 +2,82 @@ use std::env;
 use std::fs::File;
 use std::io::{self, BufRead};
 use std::path::Path;
-use std::convert::TryInto;
+use std::thread;
 
-#[derive(Clone)]
-enum SnailfishNumber {
-  Single(u8),
-  Pair(Box<SnailfishNumber>, Box<SnailfishNumber>),
-}
-
-impl std::fmt::Display for SnailfishNumber {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    match self {
-      SnailfishNumber::Single(v) => write!(f, "{}", v),
-      SnailfishNumber::Pair(left, right) => write!(f, "[{},{}]", (*left), (*right)),
-    }
-  }
-}
-
-impl SnailfishNumber {
-  fn parse(str: String) -> Option<SnailfishNumber> {
-    SnailfishNumber::_parse(&mut str.chars())
-  }
-
-  fn _parse<'a>(str: &mut std::str::Chars<'a>) -> Option<SnailfishNumber> {
-    let v = str.next()?;
+#[path = "../../common/rng.rs"]
+mod rng;
+#[path = "../snailfish.rs"]
+mod snailfish;
 
-    match v {
-      '[' => {
-        let left = SnailfishNumber::_parse(str)?;
-        if str.next()? != ',' {
-          return None
-        }
-        let right = SnailfishNumber::_parse(str)?;
-        if str.next()? != ']' {
-          return None
-        }
+use snailfish::{Rules, SnailfishNumber};
 
-        Some(SnailfishNumber::Pair(Box::new(left), Box::new(right)))
-      },
-      _ => Some(SnailfishNumber::Single(v.to_digit(10)?.try_into().ok()?))
-    }
+fn parse_line(line: String, rules: &Rules) -> SnailfishNumber {
+  match SnailfishNumber::parse(&line, rules) {
+    Some(num) => num,
+    None => panic!("Invalid snailfish number {}", line),
   }
+}
 
-  fn find_leftmost_number(&mut self) -> &mut u8 {
-    match self {
-      SnailfishNumber::Single(ref mut v) => v,
-      SnailfishNumber::Pair(ref mut left, _) => (*left).find_leftmost_number(),
+fn main() {
+  let mut rules = Rules::default();
+  let mut trace = false;
+  let mut threads = thread::available_parallelism().map_or(1, |n| n.get());
+  let mut generate: Option<usize> = None;
+  let mut depth = rules.explode_depth;
+  let mut seed = 2021_u64;
+  let mut files: Vec<String> = Vec::new();
+
+  let mut args = env::args().skip(1);
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "--trace" => trace = true,
+      "--explode-depth" => rules.explode_depth = args.next().and_then(|v| v.parse().ok()).expect("Expected a depth"),
+      "--split-threshold" => rules.split_threshold = args.next().and_then(|v| v.parse().ok()).filter(|v| *v >= 2).expect("Expected a threshold of at least 2"),
+      "--digit-width" => rules.digit_width = args.next().and_then(|v| v.parse().ok()).expect("Expected a width"),
+      "--threads" => threads = args.next().and_then(|v| v.parse().ok()).expect("Expected a thread count"),
+      "--generate" => generate = Some(args.next().and_then(|v| v.parse().ok()).expect("Expected a count")),
+      "--depth" => depth = args.next().and_then(|v| v.parse().ok()).expect("Expected a depth"),
+      "--seed" => seed = args.next().and_then(|v| v.parse().ok()).expect("Expected a seed"),
+      _ => files.push(arg),
     }
   }
 
-  fn find_rightmost_number(&mut self) -> &mut u8 {
-    match self {
-      SnailfishNumber::Single(ref mut v) => v,
-      SnailfishNumber::Pair(_, ref mut right) => (*right).find_rightmost_number(),
+  if let Some(count) = generate {
+    for num in snailfish::random_homework(count, depth, seed, &rules) {
+      println!("{}", num);
     }
+    return;
   }
 
-  fn single_val(&self) -> Option<u8> {
-    match self {
-      SnailfishNumber::Single(v) => Some(*v),
-      _ => None
-    }
+  if files.len() != 1 {
+    panic!("Expected exactly 1 input file, got {}", files.len());
   }
 
-  fn explode(&mut self) -> Option<bool> {
-    let mut ignored_left = 0_u8;
-    let mut ignored_right = 0_u8;
+  if let Ok(lines) = read_lines(&files[0]) {
+    let nums: Vec<SnailfishNumber> = lines.map(|line| parse_line(line.unwrap(), &rules)).collect();
 
-    self._explode(0, &mut ignored_left, &mut ignored_right)
-  }
+    let max_magnitude = if trace {
+      let mut max_magnitude = 0_u64;
 
-  fn _explode(&mut self, depth: u8, left: &mut u8, right: &mut u8) -> Option<bool> {
-    if let SnailfishNumber::Pair(ref mut a, ref mut b) = self {
-      if depth < 3 {
-        if (*a)._explode(depth + 1, left, (*b).find_leftmost_number())? {
-          return Some(true);
-        }
+      for (i, m) in nums.iter().enumerate() {
+        for (j, n) in nums.iter().enumerate() {
+          if i != j {
+            let mut num = m + n;
 
-        return Some((*b)._explode(depth + 1, (*a).find_rightmost_number(), right)?);
-      } else {
-        // We're at depth 4, if a or b are pairs they need to explode
-        if let SnailfishNumber::Pair(a1, a2) = &**a {
-          *left += (*a1).single_val()?;
-          *(*b).find_leftmost_number() += (*a2).single_val()?;
-          
-          **a = SnailfishNumber::Single(0);
-          Some(true)
-        } else if let SnailfishNumber::Pair(b1, b2) = &**b {
-          *(*a).find_rightmost_number() += (*b1).single_val()?;
-          *right += (*b2).single_val()?;
-          
-          **b = SnailfishNumber::Single(0);
-          Some(true)
-        } else {
-          Some(false)
-        }
-      }
-    } else {
-      Some(false)
-    }
-  }
+            println!("{} + {}", m, n);
+            println!("{:<16}{}", "after addition:", num);
+            num.reduce_traced(&rules);
+            println!();
 
-  fn split(&mut self) -> Option<bool> {
-    if let SnailfishNumber::Pair(ref mut a, ref mut b) = self {
-      match **a {
-        SnailfishNumber::Single(v) => {
-          if v >= 10 {
-            **a = (*a)._split()?;
-            return Some(true)
-          }
-        },
-        SnailfishNumber::Pair(_, _) => {
-          if (*a).split()? {
-            return Some(true)
+            max_magnitude = max_magnitude.max(num.magnitude());
           }
         }
       }
 
-      match **b {
-        SnailfishNumber::Single(v) => {
-          if v >= 10 {
-            **b = (*b)._split()?;
-            Some(true)
-          } else {
-            Some(false)
-          }
-        },
-        SnailfishNumber::Pair(_, _) => {
-          (*b).split()
-        }
-      }
+      max_magnitude
     } else {
-      Some(false)
-    }
-  }
-
-  fn _split(&self) -> Option<SnailfishNumber> {
-    match self {
-      SnailfishNumber::Single(v) => {
-        let v_f = *v as f32;
-        let left = (v_f / 2_f32).floor() as u8;
-        let right = (v_f / 2_f32).ceil() as u8;
-
-        Some(
-          SnailfishNumber::Pair(
-            Box::new(SnailfishNumber::Single(left)),
-            Box::new(SnailfishNumber::Single(right)),
-          )
-        )
-      },
-      _ => None,
-    }
-  }
-
-  fn reduce(&mut self) -> Option<()> {
-    // println!("reducing {}", self);
-    loop {
-      if self.explode()? {
-        // println!("exploded to {}", self);
-        continue;
-      }
-
-      if self.split()? {
-        // println!("split to {}", self);
-        continue;
-      }
-
-      break;
-    }
-
-    Some(())
-  }
-
-  fn magnitude(&self) -> u64 {
-    match self {
-      SnailfishNumber::Single(v) => *v as u64,
-      SnailfishNumber::Pair(a, b) => 3 * (*a).magnitude() + 2 * (*b).magnitude(),
-    }
-  }
-}
-
-impl std::ops::Add for SnailfishNumber {
-  type Output = Self;
-
-  fn add(self, other: Self) -> Self {
-    SnailfishNumber::Pair(
-      Box::new(self),
-      Box::new(other),
-    )
-  }
-}
-
-impl std::ops::Add for &SnailfishNumber {
-  type Output = SnailfishNumber;
-
-  fn add(self, other: Self) -> SnailfishNumber {
-    SnailfishNumber::Pair(
-      Box::new(self.clone()),
-      Box::new(other.clone()),
-    )
-  }
-}
-
-fn main() {
-  let args: Vec<String> = env::args().collect();
-
-  if args.len() != 2 {
-    panic!("Expected exactly 1 argument, got {}", args.len());
-  }
-
-  if let Ok(lines) = read_lines(&args[1]) {
-    let nums: Vec<SnailfishNumber> = lines.map(|line| SnailfishNumber::parse(line.unwrap()).unwrap()).collect();
-
-    let mut max_magnitude = 0_u64;
-
-    for (i, m) in nums.iter().enumerate() {
-      for (j, n) in nums.iter().enumerate() {
-        if i != j {
-          let mut num = m + n;
-          num.reduce().unwrap();
-
-          let magnitude = num.magnitude();
-          if magnitude > max_magnitude {
-            max_magnitude = magnitude;
-          }
-        }
-      }
-    }
+      snailfish::max_pair_magnitude(&nums, &rules, threads).map_or(0, |(_, _, magnitude)| magnitude)
+    };
 
     println!("{}", max_magnitude);
   } else {
@@ -242,4 +91,4 @@ fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
 where P: AsRef<Path>, {
     let file = File::open(filename)?;
     Ok(io::BufReader::new(file).lines())
-}
\ No newline at end of file
+}
@@ -0,0 +1,346 @@
+#![allow(dead_code)]
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::thread;
+
+use crate::rng::Rng;
+
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub struct Rules {
+  // Pairs nested inside this many pairs explode
+  pub explode_depth: u8,
+  // Regular numbers greater than or equal to this split, at least 2 or splitting never ends
+  pub split_threshold: u32,
+  // Maximum number of digits in a regular number when parsing
+  pub digit_width: usize,
+}
+
+impl Default for Rules {
+  fn default() -> Rules {
+    Rules { explode_depth: 4, split_threshold: 10, digit_width: 2 }
+  }
+}
+
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+struct Leaf {
+  depth: u8,
+  value: u32,
+}
+
+// A snailfish number stored as its regular numbers in left-to-right order, each tagged with the
+// number of pairs it is nested in. The tree shape follows from the depths alone.
+#[derive(Clone,PartialEq,Eq,Debug)]
+pub struct SnailfishNumber {
+  leaves: Vec<Leaf>,
+}
+
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum Step {
+  Explode,
+  Split,
+}
+
+impl fmt::Display for Step {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Step::Explode => write!(f, "explode"),
+      Step::Split => write!(f, "split"),
+    }
+  }
+}
+
+impl fmt::Display for SnailfishNumber {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    // One entry per open pair, true once its left element has been written
+    let mut open: Vec<bool> = Vec::new();
+
+    for leaf in self.leaves.iter() {
+      while open.len() < leaf.depth as usize {
+        write!(f, "[")?;
+        open.push(false);
+      }
+
+      write!(f, "{}", leaf.value)?;
+
+      while open.last() == Some(&true) {
+        write!(f, "]")?;
+        open.pop();
+      }
+
+      if let Some(top) = open.last_mut() {
+        *top = true;
+        write!(f, ",")?;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl SnailfishNumber {
+  pub fn parse(str: &str, rules: &Rules) -> Option<SnailfishNumber> {
+    let mut leaves = Vec::new();
+    // Progress through each open pair: 0 before the left element, 1 after it, 2 after the comma,
+    // 3 after the right element
+    let mut open: Vec<u8> = Vec::new();
+    let mut chars = str.trim().chars().peekable();
+
+    while let Some(c) = chars.next() {
+      let expected = match c {
+        '[' | '0'..='9' => if open.is_empty() && !leaves.is_empty() { return None } else { [0, 2] },
+        ',' => [1, 1],
+        ']' => [3, 3],
+        _ => return None,
+      };
+
+      match open.last() {
+        Some(progress) if !expected.contains(progress) => return None,
+        // Commas and closing brackets only make sense inside a pair
+        None if c == ',' || c == ']' => return None,
+        _ => {},
+      }
+
+      match c {
+        '[' => {
+          if let Some(progress) = open.last_mut() {
+            *progress += 1;
+          }
+          open.push(0);
+          continue;
+        },
+        ']' => {
+          open.pop();
+          // Counts as the element of the enclosing pair, already accounted for when it opened
+          continue;
+        },
+        ',' => {},
+        _ => {
+          let mut value = c.to_digit(10)?;
+          let mut width = 1;
+          while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+            width += 1;
+            if width > rules.digit_width {
+              return None;
+            }
+
+            value = value.checked_mul(10)?.checked_add(digit)?;
+            chars.next();
+          }
+
+          leaves.push(Leaf { depth: u8::try_from(open.len()).ok()?, value });
+        },
+      }
+
+      if let Some(progress) = open.last_mut() {
+        *progress += 1;
+      }
+    }
+
+    if !open.is_empty() || leaves.is_empty() {
+      return None;
+    }
+
+    Some(SnailfishNumber { leaves })
+  }
+
+  fn explode(&mut self, rules: &Rules) -> bool {
+    // The first two adjacent leaves with equal depth always form a pair
+    let idx = match self.leaves.windows(2).position(|w| w[0].depth > rules.explode_depth && w[0].depth == w[1].depth) {
+      Some(idx) => idx,
+      None => return false,
+    };
+
+    let Leaf { depth, value: left } = self.leaves[idx];
+    let right = self.leaves[idx + 1].value;
+
+    if idx > 0 {
+      self.leaves[idx - 1].value += left;
+    }
+    if idx + 2 < self.leaves.len() {
+      self.leaves[idx + 2].value += right;
+    }
+
+    self.leaves[idx] = Leaf { depth: depth - 1, value: 0 };
+    self.leaves.remove(idx + 1);
+
+    true
+  }
+
+  fn split(&mut self, rules: &Rules) -> bool {
+    let idx = match self.leaves.iter().position(|leaf| leaf.value >= rules.split_threshold) {
+      Some(idx) => idx,
+      None => return false,
+    };
+
+    let Leaf { depth, value } = self.leaves[idx];
+
+    self.leaves[idx] = Leaf { depth: depth + 1, value: value / 2 };
+    self.leaves.insert(idx + 1, Leaf { depth: depth + 1, value: value - value / 2 });
+
+    true
+  }
+
+  pub fn reduce_step(&mut self, rules: &Rules) -> Option<Step> {
+    if self.explode(rules) {
+      Some(Step::Explode)
+    } else if self.split(rules) {
+      Some(Step::Split)
+    } else {
+      None
+    }
+  }
+
+  pub fn reduce(&mut self, rules: &Rules) {
+    while self.reduce_step(rules).is_some() {}
+  }
+
+  pub fn reduce_traced(&mut self, rules: &Rules) {
+    while let Some(step) = self.reduce_step(rules) {
+      println!("{:<16}{}", format!("after {}:", step), self);
+    }
+  }
+
+  pub fn magnitude(&self) -> u64 {
+    let mut stack: Vec<(u8, u64)> = Vec::with_capacity(self.leaves.len());
+
+    for leaf in self.leaves.iter() {
+      let mut current = (leaf.depth, leaf.value as u64);
+
+      while let Some(&(depth, left)) = stack.last() {
+        if depth != current.0 {
+          break;
+        }
+
+        stack.pop();
+        current = (depth - 1, 3 * left + 2 * current.1);
+      }
+
+      stack.push(current);
+    }
+
+    stack.first().map_or(0, |(_, value)| *value)
+  }
+}
+
+impl std::ops::Add for &SnailfishNumber {
+  type Output = SnailfishNumber;
+
+  fn add(self, other: Self) -> SnailfishNumber {
+    let mut leaves = Vec::with_capacity(self.leaves.len() + other.leaves.len());
+
+    leaves.extend(self.leaves.iter().chain(other.leaves.iter()).map(|leaf| Leaf { depth: leaf.depth + 1, value: leaf.value }));
+
+    SnailfishNumber { leaves }
+  }
+}
+
+impl std::ops::Add for SnailfishNumber {
+  type Output = Self;
+
+  fn add(mut self, other: Self) -> Self {
+    self.leaves.extend(other.leaves);
+
+    for leaf in self.leaves.iter_mut() {
+      leaf.depth += 1;
+    }
+
+    self
+  }
+}
+
+// Largest magnitude a fully reduced number can have: every leaf at the explode depth holding the largest
+// value that doesn't split
+fn max_reduced_magnitude(rules: &Rules) -> u64 {
+  (rules.split_threshold.saturating_sub(1) as u64).saturating_mul(5_u64.saturating_pow(rules.explode_depth as u32))
+}
+
+// Finds the ordered pair of distinct entries whose reduced sum has the largest magnitude, returning the
+// indices of the pair and that magnitude. Work is spread over `threads` threads. Entries that are equal
+// to an earlier entry are skipped, and the search stops as soon as the largest possible magnitude is found.
+pub fn max_pair_magnitude(nums: &[SnailfishNumber], rules: &Rules, threads: usize) -> Option<(usize, usize, u64)> {
+  let unique: Vec<usize> = (0..nums.len()).filter(|i| !nums[..*i].contains(&nums[*i])).collect();
+  // Adding an entry to itself is only allowed if it appears twice
+  let twins: Vec<Option<usize>> = unique.iter().map(|i| (i + 1..nums.len()).find(|j| nums[*j] == nums[*i])).collect();
+  let ceiling = max_reduced_magnitude(rules);
+  let found_ceiling = AtomicBool::new(false);
+  let threads = threads.max(1);
+
+  let search = |offset: usize| {
+    let mut best: Option<(usize, usize, u64)> = None;
+
+    for (a, &i) in unique.iter().enumerate().skip(offset).step_by(threads) {
+      for &j in unique.iter() {
+        let j = if i != j {
+          j
+        } else if let Some(twin) = twins[a] {
+          twin
+        } else {
+          continue;
+        };
+
+        if found_ceiling.load(AtomicOrdering::Relaxed) {
+          return best;
+        }
+
+        let mut num = &nums[i] + &nums[j];
+        num.reduce(rules);
+
+        let magnitude = num.magnitude();
+        if best.is_none_or(|(_, _, max)| magnitude > max) {
+          best = Some((i, j, magnitude));
+
+          if magnitude >= ceiling {
+            found_ceiling.store(true, AtomicOrdering::Relaxed);
+          }
+        }
+      }
+    }
+
+    best
+  };
+
+  let results: Vec<Option<(usize, usize, u64)>> = thread::scope(|scope| {
+    let handles: Vec<_> = (0..threads).map(|offset| {
+      let search = &search;
+      scope.spawn(move || search(offset))
+    }).collect();
+
+    handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+  });
+
+  results.into_iter().flatten().fold(None, |best: Option<(usize, usize, u64)>, candidate| {
+    match best {
+      Some((_, _, max)) if max >= candidate.2 => best,
+      _ => Some(candidate),
+    }
+  })
+}
+
+impl SnailfishNumber {
+  // Generates a random reduced snailfish number with pairs nested at most `max_depth` deep (capped to the
+  // explode depth) and regular numbers below the split threshold.
+  pub fn random(rng: &mut Rng, max_depth: u8, rules: &Rules) -> SnailfishNumber {
+    let max_depth = max_depth.clamp(1, rules.explode_depth);
+    let mut leaves = Vec::new();
+
+    // Depths still to be filled, the top level is always a pair
+    let mut pending: Vec<u8> = vec![1, 1];
+    while let Some(depth) = pending.pop() {
+      if depth < max_depth && rng.below(5) < 3 {
+        pending.push(depth + 1);
+        pending.push(depth + 1);
+      } else {
+        leaves.push(Leaf { depth, value: rng.below(rules.split_threshold as u64) as u32 });
+      }
+    }
+
+    SnailfishNumber { leaves }
+  }
+}
+
+pub fn random_homework(count: usize, max_depth: u8, seed: u64, rules: &Rules) -> Vec<SnailfishNumber> {
+  let mut rng = Rng::new(seed);
+
+  (0..count).map(|_| SnailfishNumber::random(&mut rng, max_depth, rules)).collect()
+}
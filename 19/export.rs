@@ -0,0 +1,154 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::scanner::{Cluster, Orientation, Point, ScannerReport};
+
+struct Beacon {
+  cluster: usize,
+  point: Point,
+}
+
+struct Scanner {
+  cluster: usize,
+  id: usize,
+  position: Point,
+  orientation: Orientation,
+}
+
+fn beacons(clusters: &[Cluster], reports: &[ScannerReport]) -> Vec<Beacon> {
+  let mut beacons = Vec::new();
+
+  for (cluster, c) in clusters.iter().enumerate() {
+    let mut points: Vec<Point> = c.beacons(reports).into_iter().collect();
+    points.sort_by_key(|p| (p.x, p.y, p.z));
+
+    beacons.extend(points.into_iter().map(|point| Beacon { cluster, point }));
+  }
+
+  beacons
+}
+
+fn scanners(clusters: &[Cluster], reports: &[ScannerReport]) -> Vec<Scanner> {
+  clusters.iter().enumerate()
+    .flat_map(|(cluster, c)| c.scanners.iter().map(move |(idx, placement)| Scanner {
+      cluster,
+      id: reports[*idx].id,
+      position: placement.position,
+      orientation: placement.orientation,
+    }))
+    .collect()
+}
+
+// Row-major rotation matrix taking a vector as seen by the scanner to absolute coordinates
+fn rotation_matrix(orientation: &Orientation) -> [i32; 9] {
+  let Orientation { positive_x: x, positive_y: y, positive_z: z } = orientation;
+
+  [x.x, x.y, x.z, y.x, y.y, y.z, z.x, z.y, z.z]
+}
+
+fn join(values: &[i32], separator: &str) -> String {
+  values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(separator)
+}
+
+pub fn write_csv<W: Write>(out: &mut W, clusters: &[Cluster], reports: &[ScannerReport]) -> io::Result<()> {
+  writeln!(out, "kind,id,cluster,x,y,z,r00,r01,r02,r10,r11,r12,r20,r21,r22")?;
+
+  for scanner in scanners(clusters, reports) {
+    let Point { x, y, z } = scanner.position;
+    writeln!(out, "scanner,{},{},{},{},{},{}", scanner.id, scanner.cluster, x, y, z, join(&rotation_matrix(&scanner.orientation), ","))?;
+  }
+
+  for beacon in beacons(clusters, reports) {
+    let Point { x, y, z } = beacon.point;
+    writeln!(out, "beacon,,{},{},{},{},,,,,,,,,", beacon.cluster, x, y, z)?;
+  }
+
+  Ok(())
+}
+
+// Scanners and beacons are both vertices, coloured red and white. Scanner orientations go in a separate
+// element that viewers ignore.
+pub fn write_ply<W: Write>(out: &mut W, clusters: &[Cluster], reports: &[ScannerReport]) -> io::Result<()> {
+  let scanners = scanners(clusters, reports);
+  let beacons = beacons(clusters, reports);
+
+  writeln!(out, "ply")?;
+  writeln!(out, "format ascii 1.0")?;
+  writeln!(out, "comment advent of code 2021 day 19 beacon map")?;
+  writeln!(out, "element vertex {}", scanners.len() + beacons.len())?;
+  writeln!(out, "property int x")?;
+  writeln!(out, "property int y")?;
+  writeln!(out, "property int z")?;
+  writeln!(out, "property uchar red")?;
+  writeln!(out, "property uchar green")?;
+  writeln!(out, "property uchar blue")?;
+  writeln!(out, "property int cluster")?;
+  writeln!(out, "element scanner {}", scanners.len())?;
+  writeln!(out, "property int id")?;
+  writeln!(out, "property int vertex_index")?;
+  for row in 0..3 {
+    for col in 0..3 {
+      writeln!(out, "property int r{}{}", row, col)?;
+    }
+  }
+  writeln!(out, "end_header")?;
+
+  for scanner in scanners.iter() {
+    let Point { x, y, z } = scanner.position;
+    writeln!(out, "{} {} {} 255 0 0 {}", x, y, z, scanner.cluster)?;
+  }
+
+  for beacon in beacons.iter() {
+    let Point { x, y, z } = beacon.point;
+    writeln!(out, "{} {} {} 255 255 255 {}", x, y, z, beacon.cluster)?;
+  }
+
+  for (i, scanner) in scanners.iter().enumerate() {
+    writeln!(out, "{} {} {}", scanner.id, i, join(&rotation_matrix(&scanner.orientation), " "))?;
+  }
+
+  Ok(())
+}
+
+// One object per cluster with groups for scanners and beacons, orientations are written as comments.
+pub fn write_obj<W: Write>(out: &mut W, clusters: &[Cluster], reports: &[ScannerReport]) -> io::Result<()> {
+  let scanners = scanners(clusters, reports);
+  let beacons = beacons(clusters, reports);
+
+  writeln!(out, "# advent of code 2021 day 19 beacon map")?;
+
+  for cluster in 0..clusters.len() {
+    writeln!(out, "o cluster_{}", cluster)?;
+
+    writeln!(out, "g scanners")?;
+    for scanner in scanners.iter().filter(|s| s.cluster == cluster) {
+      let Point { x, y, z } = scanner.position;
+      writeln!(out, "# scanner {} rotation {}", scanner.id, join(&rotation_matrix(&scanner.orientation), " "))?;
+      writeln!(out, "v {} {} {}", x, y, z)?;
+    }
+
+    writeln!(out, "g beacons")?;
+    for beacon in beacons.iter().filter(|b| b.cluster == cluster) {
+      let Point { x, y, z } = beacon.point;
+      writeln!(out, "v {} {} {}", x, y, z)?;
+    }
+  }
+
+  Ok(())
+}
+
+// Picks the format based on the file extension.
+pub fn export(path: &str, clusters: &[Cluster], reports: &[ScannerReport]) -> io::Result<()> {
+  let extension = Path::new(path).extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase());
+  let write = match extension.as_deref() {
+    Some("csv") => write_csv::<BufWriter<File>>,
+    Some("ply") => write_ply::<BufWriter<File>>,
+    Some("obj") => write_obj::<BufWriter<File>>,
+    _ => return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Unknown export format for {}, expected .csv, .ply or .obj", path))),
+  };
+
+  let mut out = BufWriter::new(File::create(path)?);
+  write(&mut out, clusters, reports)?;
+  out.flush()
+}
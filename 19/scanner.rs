@@ -0,0 +1,557 @@
+#![allow(dead_code)]
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::ops;
+
+#[derive(PartialEq,Eq,Hash,Copy,Clone)]
+pub struct Point {
+  pub x: i32,
+  pub y: i32,
+  pub z: i32,
+}
+
+impl Point {
+  pub const ORIGIN: Point = Point { x: 0, y: 0, z: 0 };
+
+  pub fn as_vector(&self) -> Vector {
+    Vector { x: self.x, y: self.y, z: self.z }
+  }
+
+  pub fn manhattan_distance(&self, other: &Point) -> i32 {
+    (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+  }
+}
+
+#[derive(PartialEq,Eq,Hash,Copy,Clone)]
+pub struct Vector {
+  pub x: i32,
+  pub y: i32,
+  pub z: i32,
+}
+
+impl Vector {
+  pub fn len(&self) -> f32 {
+    (self.dot(self) as f32).sqrt()
+  }
+
+  pub fn dot(&self, rhs: &Vector) -> i32 {
+    self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
+  }
+
+  pub fn cross(&self, rhs: &Vector) -> Vector {
+    Vector {
+      x: self.y * rhs.z - self.z * rhs.y,
+      y: self.z * rhs.x - self.x * rhs.z,
+      z: self.x * rhs.y - self.y * rhs.x,
+    }
+  }
+}
+
+impl std::fmt::Display for Point {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "Point ({}, {}, {})", self.x, self.y, self.z)
+  }
+}
+
+impl std::fmt::Display for Vector {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "Vector ({}, {}, {})", self.x, self.y, self.z)
+  }
+}
+
+impl ops::Add<Vector> for &Point {
+  type Output = Point;
+
+  fn add(self, rhs: Vector) -> Point {
+    Point {
+      x: self.x + rhs.x,
+      y: self.y + rhs.y,
+      z: self.z + rhs.z,
+    }
+  }
+}
+
+impl ops::Add<&Vector> for &Point {
+  type Output = Point;
+
+  fn add(self, rhs: &Vector) -> Point {
+    Point {
+      x: self.x + rhs.x,
+      y: self.y + rhs.y,
+      z: self.z + rhs.z,
+    }
+  }
+}
+
+impl ops::Add<Vector> for Point {
+  type Output = Point;
+
+  fn add(self, rhs: Vector) -> Point {
+    Point {
+      x: self.x + rhs.x,
+      y: self.y + rhs.y,
+      z: self.z + rhs.z,
+    }
+  }
+}
+
+impl ops::Add<&Vector> for &Vector {
+  type Output = Vector;
+
+  fn add(self, rhs: &Vector) -> Vector {
+    Vector {
+      x: self.x + rhs.x,
+      y: self.y + rhs.y,
+      z: self.z + rhs.z,
+    }
+  }
+}
+
+impl ops::Add<Vector> for Vector {
+  type Output = Vector;
+
+  fn add(self, rhs: Vector) -> Vector {
+    Vector {
+      x: self.x + rhs.x,
+      y: self.y + rhs.y,
+      z: self.z + rhs.z,
+    }
+  }
+}
+
+impl ops::Sub<&Vector> for &Point {
+  type Output = Point;
+
+  fn sub(self, rhs: &Vector) -> Point {
+    Point {
+      x: self.x - rhs.x,
+      y: self.y - rhs.y,
+      z: self.z - rhs.z,
+    }
+  }
+}
+
+impl ops::Sub<Vector> for Point {
+  type Output = Point;
+
+  fn sub(self, rhs: Vector) -> Point {
+    Point {
+      x: self.x - rhs.x,
+      y: self.y - rhs.y,
+      z: self.z - rhs.z,
+    }
+  }
+}
+
+impl ops::Sub<&Vector> for &Vector {
+  type Output = Vector;
+
+  fn sub(self, rhs: &Vector) -> Vector {
+    Vector {
+      x: self.x - rhs.x,
+      y: self.y - rhs.y,
+      z: self.z - rhs.z,
+    }
+  }
+}
+
+impl ops::Sub<Vector> for Vector {
+  type Output = Vector;
+
+  fn sub(self, rhs: Vector) -> Vector {
+    Vector {
+      x: self.x - rhs.x,
+      y: self.y - rhs.y,
+      z: self.z - rhs.z,
+    }
+  }
+}
+
+impl ops::Sub<&Point> for &Point {
+  type Output = Vector;
+
+  fn sub(self, rhs: &Point) -> Vector {
+    Vector {
+      x: self.x - rhs.x,
+      y: self.y - rhs.y,
+      z: self.z - rhs.z,
+    }
+  }
+}
+
+impl ops::Sub<Point> for Point {
+  type Output = Vector;
+
+  fn sub(self, rhs: Point) -> Vector {
+    Vector {
+      x: self.x - rhs.x,
+      y: self.y - rhs.y,
+      z: self.z - rhs.z,
+    }
+  }
+}
+
+impl ops::Mul<i32> for &Vector {
+  type Output = Vector;
+
+  fn mul(self, rhs: i32) -> Vector {
+    Vector {
+      x: self.x * rhs,
+      y: self.y * rhs,
+      z: self.z * rhs,
+    }
+  }
+}
+
+impl ops::Mul<i32> for Vector {
+  type Output = Vector;
+
+  fn mul(self, rhs: i32) -> Vector {
+    Vector {
+      x: self.x * rhs,
+      y: self.y * rhs,
+      z: self.z * rhs,
+    }
+  }
+}
+
+impl ops::Div<i32> for &Vector {
+  type Output = Vector;
+
+  fn div(self, rhs: i32) -> Vector {
+    Vector {
+      x: self.x / rhs,
+      y: self.y / rhs,
+      z: self.z / rhs,
+    }
+  }
+}
+
+impl ops::Div<i32> for Vector {
+  type Output = Vector;
+
+  fn div(self, rhs: i32) -> Vector {
+    Vector {
+      x: self.x / rhs,
+      y: self.y / rhs,
+      z: self.z / rhs,
+    }
+  }
+}
+
+#[derive(PartialEq,Eq,Hash,Copy,Clone)]
+pub struct Orientation {
+  pub positive_x: Vector,
+  pub positive_y: Vector,
+  pub positive_z: Vector,
+}
+
+impl Orientation {
+  pub fn create(x: Vector, y: Vector) -> Orientation {
+    let z = x.cross(&y);
+    Orientation { positive_x: x, positive_y: y, positive_z: z }
+  }
+
+  pub fn all() -> HashSet<Orientation> {
+    let mut all = HashSet::new();
+
+    let real_x = Vector { x: 1, y: 0, z: 0 };
+    let real_y = Vector { x: 0, y: 1, z: 0 };
+    let real_z = Vector { x: 0, y: 0, z: 1 };
+
+    for x_mult in [1, -1] {
+      for y_mult in [1, -1] {
+        all.insert(Orientation::create(real_x * x_mult, real_y * y_mult));
+        all.insert(Orientation::create(real_x * x_mult, real_z * y_mult));
+        all.insert(Orientation::create(real_y * x_mult, real_x * y_mult));
+        all.insert(Orientation::create(real_y * x_mult, real_z * y_mult));
+        all.insert(Orientation::create(real_z * x_mult, real_x * y_mult));
+        all.insert(Orientation::create(real_z * x_mult, real_y * y_mult));
+      }
+    }
+
+    assert_eq!(all.len(), 24);
+
+    all
+  }
+
+  pub fn map(&self, absolute_vector: &Vector) -> Vector {
+    self.positive_x * absolute_vector.x + self.positive_y * absolute_vector.y + self.positive_z * absolute_vector.z
+  }
+
+  pub fn unmap(&self, vector: Vector) -> Vector {
+    Vector {
+      x: vector.dot(&self.positive_x),
+      y: vector.dot(&self.positive_y),
+      z: vector.dot(&self.positive_z),
+    }
+  }
+}
+
+
+pub struct ScannerReport {
+  pub id: usize,
+  pub points: Vec<Point>,
+}
+
+#[derive(Debug)]
+pub struct ParseError {
+  pub line: usize,
+  pub message: String,
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "line {}: {}", self.line, self.message)
+  }
+}
+
+// None if the line isn't a header, Some(None) if it is a header without a valid scanner ID
+fn parse_header(line: &str) -> Option<Option<usize>> {
+  let inner = line.strip_prefix("---").and_then(|l| l.strip_suffix("---"))?;
+
+  let words: Vec<&str> = inner.split_whitespace().collect();
+  match words[..] {
+    ["scanner", id] => Some(id.parse().ok()),
+    _ => Some(None),
+  }
+}
+
+// Accepts `x,y,z` or `x,y`, the latter is placed in the z = 0 plane.
+fn parse_point(line: &str) -> Option<Point> {
+  let parts: Vec<i32> = line.split(',').map(|part| part.trim().parse().ok()).collect::<Option<_>>()?;
+
+  match parts[..] {
+    [x, y, z] => Some(Point { x, y, z }),
+    [x, y] => Some(Point { x, y, z: 0 }),
+    _ => None,
+  }
+}
+
+// Parses `--- scanner N ---` headers each followed by one beacon per line. Blank lines and surrounding
+// whitespace are ignored. Line numbers in errors start at 1.
+pub fn parse_reports<I: Iterator<Item = String>>(lines: I) -> Result<Vec<ScannerReport>, ParseError> {
+  let mut reports: Vec<ScannerReport> = Vec::new();
+  let mut line_count = 0;
+
+  for (i, l) in lines.enumerate() {
+    line_count = i + 1;
+    let line = l.trim();
+    let error = |message: String| Err(ParseError { line: i + 1, message });
+
+    if line.is_empty() {
+      continue;
+    }
+
+    match parse_header(line) {
+      Some(Some(id)) => {
+        if reports.iter().any(|report| report.id == id) {
+          return error(format!("Duplicate scanner {}", id));
+        }
+
+        reports.push(ScannerReport { id, points: Vec::new() });
+      },
+      Some(None) => return error(format!("Invalid scanner header \"{}\"", line)),
+      None => {
+        let point = match parse_point(line) {
+          Some(point) => point,
+          None => return error(format!("Invalid point \"{}\"", line)),
+        };
+
+        match reports.last_mut() {
+          Some(report) => report.points.push(point),
+          None => return error(String::from("Expected a scanner header before the first point")),
+        }
+      },
+    }
+  }
+
+  if reports.is_empty() {
+    // Points the error at the end of the input
+    return Err(ParseError { line: line_count + 1, message: String::from("No scanner reports") });
+  }
+
+  Ok(reports)
+}
+
+pub const DEFAULT_MIN_OVERLAP: usize = 12;
+
+#[derive(PartialEq,Eq,Hash,Copy,Clone)]
+pub struct Placement {
+  pub position: Point,
+  pub orientation: Orientation,
+}
+
+impl Placement {
+  pub fn identity() -> Placement {
+    Placement {
+      position: Point::ORIGIN,
+      orientation: Orientation::create(Vector { x: 1, y: 0, z: 0 }, Vector { x: 0, y: 1, z: 0 }),
+    }
+  }
+
+  pub fn to_absolute(self, point: &Point) -> Point {
+    self.position + self.orientation.unmap(point - &Point::ORIGIN)
+  }
+}
+
+fn squared_distance(a: &Point, b: &Point) -> i64 {
+  let d = a - b;
+  (d.x as i64).pow(2) + (d.y as i64).pow(2) + (d.z as i64).pow(2)
+}
+
+// Sorted squared distances between every pair of beacons seen by a scanner, these don't depend on the
+// scanner's position or orientation.
+pub fn fingerprint(points: &[Point]) -> Vec<i64> {
+  let mut distances = Vec::with_capacity(points.len() * points.len().saturating_sub(1) / 2);
+
+  for (i, a) in points.iter().enumerate() {
+    for b in points[i + 1..].iter() {
+      distances.push(squared_distance(a, b));
+    }
+  }
+
+  distances.sort_unstable();
+  distances
+}
+
+// Size of the multiset intersection of two fingerprints.
+pub fn shared_distances(a: &[i64], b: &[i64]) -> usize {
+  let (mut i, mut j, mut shared) = (0, 0, 0);
+
+  while i < a.len() && j < b.len() {
+    if a[i] < b[j] {
+      i += 1;
+    } else if a[i] > b[j] {
+      j += 1;
+    } else {
+      shared += 1;
+      i += 1;
+      j += 1;
+    }
+  }
+
+  shared
+}
+
+// Tries to place the `points` of a scanner such that at least `min_overlap` of them coincide with the
+// `known` absolute beacon positions.
+pub fn align_points(known: &[Point], points: &[Point], orientations: &[Orientation], min_overlap: usize) -> Option<Placement> {
+  let known_set: HashSet<&Point> = known.iter().collect();
+
+  for orientation in orientations.iter() {
+    let rotated: Vec<Vector> = points.iter().map(|point| orientation.unmap(point - &Point::ORIGIN)).collect();
+    let mut offsets: HashMap<Point, usize> = HashMap::new();
+
+    for absolute in known.iter() {
+      for relative in rotated.iter() {
+        let position = absolute - relative;
+        let count = offsets.entry(position).or_insert(0);
+        *count += 1;
+
+        if *count >= min_overlap {
+          let num_matches = rotated.iter().filter(|relative| known_set.contains(&(position + **relative))).count();
+
+          if num_matches >= min_overlap {
+            return Some(Placement { position, orientation: *orientation });
+          }
+        }
+      }
+    }
+  }
+
+  None
+}
+
+// A group of scanners placed relative to each other, positions are relative to the first scanner in
+// the group. Scanners are referred to by their index in the list of reports.
+pub struct Cluster {
+  pub scanners: Vec<(usize, Placement)>,
+}
+
+impl Cluster {
+  pub fn beacons(&self, reports: &[ScannerReport]) -> HashSet<Point> {
+    self.scanners.iter()
+      .flat_map(|(i, placement)| reports[*i].points.iter().map(move |point| placement.to_absolute(point)))
+      .collect()
+  }
+
+  pub fn max_scanner_distance(&self) -> i32 {
+    let mut max_distance = 0_i32;
+
+    for (_, a) in self.scanners.iter() {
+      for (_, b) in self.scanners.iter() {
+        max_distance = max_distance.max(a.position.manhattan_distance(&b.position));
+      }
+    }
+
+    max_distance
+  }
+}
+
+// Groups the scanners into clusters whose members overlap with at least `min_overlap` beacons. The first
+// cluster contains the first scanner, if every scanner could be placed it is the only cluster. Scanners are only
+// compared in full when their fingerprints share enough distances to possibly have `min_overlap` beacons
+// in common.
+pub fn align(reports: &[ScannerReport], min_overlap: usize) -> Vec<Cluster> {
+  let measurements: Vec<&[Point]> = reports.iter().map(|report| &report.points[..]).collect();
+  let orientations: Vec<Orientation> = Orientation::all().into_iter().collect();
+  let fingerprints: Vec<Vec<i64>> = measurements.iter().map(|points| fingerprint(points)).collect();
+  let min_shared = min_overlap * min_overlap.saturating_sub(1) / 2;
+
+  let mut placements: Vec<Option<Placement>> = vec![None; measurements.len()];
+  let mut absolute: Vec<Vec<Point>> = vec![Vec::new(); measurements.len()];
+  let mut clusters: Vec<Cluster> = Vec::new();
+
+  for root in 0..measurements.len() {
+    if placements[root].is_some() {
+      continue;
+    }
+
+    let mut cluster = Cluster { scanners: vec![(root, Placement::identity())] };
+    let mut queue: VecDeque<usize> = VecDeque::new();
+
+    placements[root] = Some(Placement::identity());
+    absolute[root] = measurements[root].to_vec();
+    queue.push_back(root);
+
+    while let Some(placed) = queue.pop_front() {
+      for candidate in 0..measurements.len() {
+        if placements[candidate].is_some() || shared_distances(&fingerprints[placed], &fingerprints[candidate]) < min_shared {
+          continue;
+        }
+
+        if let Some(placement) = align_points(&absolute[placed], measurements[candidate], &orientations, min_overlap) {
+          absolute[candidate] = measurements[candidate].iter().map(|point| placement.to_absolute(point)).collect();
+          placements[candidate] = Some(placement);
+          cluster.scanners.push((candidate, placement));
+          queue.push_back(candidate);
+        }
+      }
+    }
+
+    clusters.push(cluster);
+  }
+
+  clusters
+}
+
+pub fn print_clusters(clusters: &[Cluster], reports: &[ScannerReport]) {
+  for (i, cluster) in clusters.iter().enumerate() {
+    let ids: Vec<String> = cluster.scanners.iter().map(|(idx, _)| reports[*idx].id.to_string()).collect();
+
+    println!(
+      "Cluster {}: {} scanners ({}), {} beacons, max scanner distance {}",
+      i,
+      cluster.scanners.len(),
+      ids.join(", "),
+      cluster.beacons(reports).len(),
+      cluster.max_scanner_distance(),
+    );
+
+    for (idx, placement) in cluster.scanners.iter() {
+      println!("- scanner {} at {}", reports[*idx].id, placement.position);
+    }
+  }
+}
@@ -0,0 +1,250 @@
+#![allow(dead_code)]
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::process;
+
+// An image enhancement algorithm, each output pixel is looked up from the square of `size` by `size`
+// pixels centred on it, read row by row as a binary number.
+pub struct Algorithm {
+  pub size: usize,
+  output: Vec<bool>,
+}
+
+impl Algorithm {
+  pub fn parse(line: &str) -> Result<Algorithm, String> {
+    let output = parse_pixels(line)?;
+
+    // 2^(size * size) entries, only odd sizes have a centre pixel
+    let size = (1..=5).step_by(2).find(|size| 1_usize << (size * size) == output.len())
+      .ok_or_else(|| format!("Algorithm has {} entries, expected 2, 512 or 33554432", output.len()))?;
+
+    Ok(Algorithm { size, output })
+  }
+
+  fn radius(&self) -> i32 {
+    (self.size / 2) as i32
+  }
+
+  fn all_ones(&self) -> usize {
+    self.output.len() - 1
+  }
+}
+
+fn parse_pixels(line: &str) -> Result<Vec<bool>, String> {
+  line.trim().chars().map(|c| match c {
+    '#' => Ok(true),
+    '.' => Ok(false),
+    _ => Err(format!("unexpected char {}", c)),
+  }).collect()
+}
+
+/// An infinite image
+///
+/// Only the pixels that differ from the background are stored, the background covers everything else
+/// and is either all lit or all dark.
+pub struct Image {
+  pub background: bool,
+  exceptions: HashSet<(i32, i32)>,
+}
+
+impl Image {
+  pub fn parse<I: Iterator<Item = String>>(lines: I) -> Result<Image, String> {
+    let mut exceptions = HashSet::new();
+
+    for (y, line) in lines.enumerate() {
+      for (x, lit) in parse_pixels(&line)?.into_iter().enumerate() {
+        if lit {
+          exceptions.insert((x as i32, y as i32));
+        }
+      }
+    }
+
+    Ok(Image { background: false, exceptions })
+  }
+
+  pub fn is_lit(&self, x: i32, y: i32) -> bool {
+    self.exceptions.contains(&(x, y)) != self.background
+  }
+
+  // Number of lit pixels, None when infinitely many are lit
+  pub fn lit_count(&self) -> Option<usize> {
+    if self.background {
+      None
+    } else {
+      Some(self.exceptions.len())
+    }
+  }
+
+  // Smallest rectangle holding every pixel that differs from the background, as min and exclusive max
+  pub fn bounds(&self) -> ((i32, i32), (i32, i32)) {
+    if self.exceptions.is_empty() {
+      return ((0, 0), (0, 0));
+    }
+
+    let (mut min, mut max) = ((i32::MAX, i32::MAX), (i32::MIN, i32::MIN));
+    for (x, y) in self.exceptions.iter() {
+      min = (min.0.min(*x), min.1.min(*y));
+      max = (max.0.max(x + 1), max.1.max(y + 1));
+    }
+
+    (min, max)
+  }
+
+  pub fn enhance(&self, algo: &Algorithm) -> Image {
+    let radius = algo.radius();
+    let background = algo.output[if self.background { algo.all_ones() } else { 0 }];
+
+    // Pixels further than the radius away from any exception only see background
+    let candidates: HashSet<(i32, i32)> = self.exceptions.iter().flat_map(|(x, y)| {
+      (-radius..=radius).flat_map(move |dy| (-radius..=radius).map(move |dx| (x + dx, y + dy)))
+    }).collect();
+
+    let exceptions = candidates.into_iter().filter(|(x, y)| {
+      let mut idx = 0_usize;
+
+      for dy in -radius..=radius {
+        for dx in -radius..=radius {
+          idx = (idx << 1) | self.is_lit(x + dx, y + dy) as usize;
+        }
+      }
+
+      algo.output[idx] != background
+    }).collect();
+
+    Image { background, exceptions }
+  }
+}
+
+// Plain (P1) portable bitmap of the pixels from `min` up to `max`, lit pixels are black
+pub fn write_pbm<W: Write>(out: &mut W, image: &Image, min: (i32, i32), max: (i32, i32)) -> io::Result<()> {
+  writeln!(out, "P1")?;
+  writeln!(out, "{} {}", max.0 - min.0, max.1 - min.1)?;
+
+  for y in min.1..max.1 {
+    let row: Vec<&str> = (min.0..max.0).map(|x| if image.is_lit(x, y) { "1" } else { "0" }).collect();
+    writeln!(out, "{}", row.join(" "))?;
+  }
+
+  Ok(())
+}
+
+// Plain (P2) portable graymap, lit pixels are white. Pixels that are part of the background are drawn
+// dimmer so the finite part of the image stands out.
+pub fn write_pgm<W: Write>(out: &mut W, image: &Image, min: (i32, i32), max: (i32, i32)) -> io::Result<()> {
+  writeln!(out, "P2")?;
+  writeln!(out, "{} {}", max.0 - min.0, max.1 - min.1)?;
+  writeln!(out, "3")?;
+
+  for y in min.1..max.1 {
+    let row: Vec<&str> = (min.0..max.0).map(|x| match (image.is_lit(x, y), image.exceptions.contains(&(x, y))) {
+      (true, true) => "3",
+      (true, false) => "2",
+      (false, false) => "1",
+      (false, true) => "0",
+    }).collect();
+    writeln!(out, "{}", row.join(" "))?;
+  }
+
+  Ok(())
+}
+
+// Writes the image with a border of `margin` background pixels, the format is picked by file extension.
+pub fn export(path: &str, image: &Image, margin: i32) -> io::Result<()> {
+  let extension = Path::new(path).extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase());
+  let write = match extension.as_deref() {
+    Some("pbm") => write_pbm::<BufWriter<File>>,
+    Some("pgm") => write_pgm::<BufWriter<File>>,
+    _ => return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Unknown export format for {}, expected .pbm or .pgm", path))),
+  };
+
+  let (min, max) = image.bounds();
+  let mut out = BufWriter::new(File::create(path)?);
+  write(&mut out, image, (min.0 - margin, min.1 - margin), (max.0 + margin, max.1 + margin))?;
+  out.flush()
+}
+
+fn describe(image: &Image) -> String {
+  let ((min_x, min_y), (max_x, max_y)) = image.bounds();
+  let lit = match image.lit_count() {
+    Some(count) => count.to_string(),
+    None => String::from("infinite"),
+  };
+
+  format!("{}x{} -> {}", max_x - min_x, max_y - min_y, lit)
+}
+
+// Command line options of both parts, which only differ in the default number of iterations
+pub struct Options {
+  pub iterations: usize,
+  pub neighbourhood: Option<usize>,
+  pub margin: i32,
+  pub exports: Vec<(usize, String)>,
+}
+
+impl Options {
+  // Returns the options and the remaining arguments, which are the input files
+  pub fn parse<I: Iterator<Item = String>>(mut args: I, iterations: usize) -> (Options, Vec<String>) {
+    let mut options = Options { iterations, neighbourhood: None, margin: 2, exports: Vec::new() };
+    let mut files: Vec<String> = Vec::new();
+
+    while let Some(arg) = args.next() {
+      match arg.as_str() {
+        "--iterations" => options.iterations = args.next().and_then(|v| v.parse().ok()).expect("Expected a number of iterations"),
+        "--neighbourhood" => options.neighbourhood = Some(args.next().and_then(|v| v.parse().ok()).expect("Expected a neighbourhood size, 3 or 5")),
+        "--margin" => options.margin = args.next().and_then(|v| v.parse().ok()).expect("Expected a margin in pixels"),
+        "--export" => options.exports.push(args.next().and_then(|v| {
+          let (iteration, path) = v.split_once(':')?;
+          Some((iteration.parse().ok()?, path.to_string()))
+        }).expect("Expected an iteration and a path, e.g. 2:image.pbm")),
+        _ => files.push(arg),
+      }
+    }
+
+    (options, files)
+  }
+}
+
+// Enhances the image read from `lines` and prints its size and lit pixels after every iteration
+pub fn run<I: Iterator<Item = String>>(lines: I, options: &Options) {
+  let mut lines = lines.filter(|line| !line.is_empty());
+
+  let algo = Algorithm::parse(&lines.next().expect("Expected an image enhancement algorithm")).unwrap_or_else(|err| {
+    eprintln!("Invalid algorithm, {}", err);
+    process::exit(1);
+  });
+  if let Some(size) = options.neighbourhood {
+    if size != algo.size {
+      eprintln!("Algorithm is for a {0}x{0} neighbourhood, expected {1}x{1}", algo.size, size);
+      process::exit(1);
+    }
+  }
+
+  let mut image = Image::parse(lines).unwrap_or_else(|err| {
+    eprintln!("Invalid image, {}", err);
+    process::exit(1);
+  });
+
+  println!("{}", describe(&image));
+
+  for iteration in 0..=options.iterations {
+    if iteration > 0 {
+      image = image.enhance(&algo);
+      println!("{}", describe(&image));
+    }
+
+    for (_, path) in options.exports.iter().filter(|(at, _)| *at == iteration) {
+      if let Err(err) = export(path, &image, options.margin) {
+        eprintln!("Failed to export {}: {}", path, err);
+        process::exit(1);
+      }
+    }
+  }
+
+  match image.lit_count() {
+    Some(count) => println!("lit: {}", count),
+    None => println!("lit: infinite"),
+  }
+}
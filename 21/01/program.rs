@@ -2,85 +2,162 @@ use std::env;
 use std::fs::File;
 use std::io::{self, BufRead};
 use std::path::Path;
+use std::process;
+
+#[path = "../analysis.rs"]
+mod analysis;
+#[path = "../die.rs"]
+mod die;
+#[path = "../game.rs"]
+mod game;
+#[path = "../../common/rng.rs"]
+mod rng;
+
+use analysis::Outcome;
+use die::{DeterministicDie, Die, RandomDie, ReplayedDie};
+use game::{GameState, Rules};
+
+// Plays until someone wins, returning the final state, the winner and the number of turns played. Returns
+// None if the die runs out of rolls first.
+fn play(rules: &Rules, die: &mut dyn Die, mut state: GameState, log: bool) -> Option<(GameState, usize, usize)> {
+  let mut turns = 0;
+
+  loop {
+    let player = state.turn;
+    let casts: Vec<u32> = (0..rules.rolls).map(|_| die.cast()).collect::<Option<_>>()?;
+
+    let (next, won) = state.play(rules, casts.iter().sum());
+    state = next;
+    turns += 1;
+
+    if log {
+      let casts: Vec<String> = casts.iter().map(|cast| cast.to_string()).collect();
+      println!("Player {} casts {} and moves to {}, score {}",
+        player + 1, casts.join("+"), state.positions[player], state.scores[player]);
+    }
 
-struct Die {
-  val: u32,
-  max: u32,
-
-  number_of_casts: u32,
-}
-
-impl Die {
-  fn new() -> Die {
-    Die { val: 1, max: 100, number_of_casts: 0 }
+    if won {
+      return Some((state, player, turns));
+    }
   }
+}
 
-  fn cast(&mut self) -> u32 {
-    let val = self.val % self.max;
-    
-    self.val += 1;
-    self.number_of_casts += 1;
-    
-    val
+// None of the dice leave anything to chance, the outcome is the one game a fresh die plays
+fn analyse(rules: &Rules, new_die: &dyn Fn() -> Box<dyn Die>, state: &GameState) -> Outcome {
+  match play(rules, new_die().as_mut(), state.clone(), false) {
+    Some((_, winner, turns)) => Outcome::certain(state.positions.len(), winner, turns),
+    None => {
+      eprintln!("The die ran out of rolls before the game from {:?} ended", state.positions);
+      process::exit(1);
+    },
   }
 }
 
-fn has_winner(scores: &Vec<u32>) -> bool {
-  scores.iter().any(|v| { *v >= 1000 })
+fn parse_list(str: &str) -> Option<Vec<u32>> {
+  str.split(',').map(|v| v.trim().parse().ok()).collect()
 }
 
 fn main() {
-  let args: Vec<String> = env::args().collect();
+  let mut rules = Rules { faces: 100, rolls: 3, board: 10, win_score: 1000 };
+  let mut seed: Option<u64> = None;
+  let mut replay: Option<String> = None;
+  let mut log = false;
+  let mut scores: Option<Vec<u32>> = None;
+  let mut turn = 1_usize;
+  let mut show_analysis = false;
+  let mut table = false;
+  let mut files: Vec<String> = Vec::new();
+
+  let mut args = env::args().skip(1);
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "--faces" => rules.faces = args.next().and_then(|v| v.parse().ok()).filter(|v| *v > 0).expect("Expected a number of die faces"),
+      "--seed" => seed = Some(args.next().and_then(|v| v.parse().ok()).expect("Expected a seed")),
+      "--replay" => replay = Some(args.next().expect("Expected a file with rolls")),
+      "--log" => log = true,
+      "--scores" => scores = Some(args.next().and_then(|v| parse_list(&v)).expect("Expected scores a,b,...")),
+      "--turn" => turn = args.next().and_then(|v| v.parse().ok()).filter(|v| *v > 0).expect("Expected the number of the player to move"),
+      "--analyse" => show_analysis = true,
+      "--table" => table = true,
+      _ => files.push(arg),
+    }
+  }
 
-  if args.len() != 2 {
-    panic!("Expected exactly 1 argument, got {}", args.len());
+  if files.len() != 1 {
+    panic!("Expected exactly 1 input file, got {}", files.len());
   }
 
-  if let Ok(lines) = read_lines(&args[1]) {
-    let mut die = Die::new();
-    let mut positions: Vec<u32> = lines.map(|line| {
-      line.unwrap().chars().last().unwrap().to_digit(10).unwrap()
-    }).collect();
-    let mut scores: Vec<u32> = positions.iter().map(|_| 0).collect();
+  let new_die: Box<dyn Fn() -> Box<dyn Die>> = match (seed, replay) {
+    (Some(_), Some(_)) => panic!("Expected either --seed or --replay, not both"),
+    (Some(seed), None) => Box::new(move || Box::new(RandomDie::new(rules.faces, seed))),
+    (None, Some(path)) => {
+      let replayed = ReplayedDie::load(&path).unwrap_or_else(|err| {
+        eprintln!("Invalid rolls, {}", err);
+        process::exit(1);
+      });
+      Box::new(move || Box::new(replayed.clone()))
+    },
+    (None, None) => Box::new(move || Box::new(DeterministicDie::new(rules.faces))),
+  };
+
+  if let Ok(lines) = read_lines(&files[0]) {
+    let mut die = new_die();
+    let positions = game::parse_positions(lines.map(|line| line.unwrap()), &rules).unwrap_or_else(|err| {
+      eprintln!("Invalid starting positions, {}", err);
+      process::exit(1);
+    });
+
+    let mut state = GameState::new(&positions);
+    if turn > positions.len() {
+      eprintln!("Expected the player to move to be from 1 to {}, got {}", positions.len(), turn);
+      process::exit(1);
+    }
+    state.turn = turn - 1;
 
-    println!("Starting positions:");
-    for (i, position) in positions.iter().enumerate() {
-      println!("- {}: {}", i, position);
+    if let Some(scores) = scores {
+      if scores.len() != positions.len() || scores.iter().any(|score| *score >= rules.win_score) {
+        eprintln!("Expected a score below {} for each of the {} players", rules.win_score, positions.len());
+        process::exit(1);
+      }
+      state.scores = scores;
     }
 
-    while !has_winner(&scores) {
-      for (i, position) in positions.iter_mut().enumerate() {
-        let cast_one = die.cast();
-        let cast_two = die.cast();
-        let cast_three = die.cast();
+    println!("Die: {}", die.name());
+    println!("Starting positions:");
+    for (i, position) in state.positions.iter().enumerate() {
+      println!("- {}: {}", i + 1, position);
+    }
 
-        // *position += die.cast() + die.cast() + die.cast();
-        *position += cast_one + cast_two + cast_three;
-        while *position > 10 {
-          *position -= 10;
-        }
+    let start = state.clone();
+    let state = match play(&rules, die.as_mut(), state, log) {
+      Some((state, _, _)) => state,
+      None => {
+        eprintln!("The die ran out of rolls after {} casts", die.number_of_casts());
+        process::exit(1);
+      },
+    };
 
-        scores[i] += *position;
+    println!("\nFinal positions:");
+    for (i, position) in state.positions.iter().enumerate() {
+      println!("{}: {}", i + 1, position);
+    }
+    println!("\nScores:");
+    for (i, score) in state.scores.iter().enumerate() {
+      println!("{}: {}", i + 1, score);
+    }
+    println!("\nNumber of casts: {}", die.number_of_casts());
 
-        println!("Player {} casts {}+{}+{} and moves to {}, score {}",
-          i, cast_one, cast_two, cast_three, *position, scores[i]);
+    println!("\nOutput: {}", die.number_of_casts() * state.scores.into_iter().find(|s| *s < rules.win_score).unwrap());
 
-        if scores[i] >= 1000 {
-          break;
-        }
-      }
+    if show_analysis {
+      println!("\nAnalysis:");
+      analyse(&rules, &new_die, &start).print();
     }
 
-    println!("\nFinal positions:");
-    for (i, position) in positions.into_iter().enumerate() {
-      println!("{}: {}", i, position);
+    if table {
+      println!();
+      analysis::print_table(rules.board, |one, two| analyse(&rules, &new_die, &GameState::new(&[one, two])));
     }
-    println!("\nScores:");
-    for (i, score) in scores.iter().enumerate() {
-      println!("{}: {}", i, score);
-    }
-    println!("\nNumber of casts: {}", die.number_of_casts);
-    println!("\nOutput: {}", die.number_of_casts * scores.into_iter().filter(|s| { *s < 1000 }).next().unwrap());
   } else {
     panic!("Failed to read file");
   }
@@ -92,4 +169,4 @@ fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
 where P: AsRef<Path>, {
     let file = File::open(filename)?;
     Ok(io::BufReader::new(file).lines())
-}
\ No newline at end of file
+}
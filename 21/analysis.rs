@@ -0,0 +1,88 @@
+#![allow(dead_code)]
+
+// How a game plays out from some state on. A turn is a single player moving once.
+#[derive(Clone,Debug)]
+pub struct Outcome {
+  pub win_probability: Vec<f64>,
+  // lengths[n] is the probability that the game ends after exactly n more turns
+  pub lengths: Vec<f64>,
+}
+
+impl Outcome {
+  pub fn new(players: usize) -> Outcome {
+    Outcome { win_probability: vec![0.0; players], lengths: Vec::new() }
+  }
+
+  // A game that is certain to be won by `winner` after `turns` more turns
+  pub fn certain(players: usize, winner: usize, turns: usize) -> Outcome {
+    let mut outcome = Outcome::new(players);
+    outcome.add_win(winner, turns, 1.0);
+    outcome
+  }
+
+  pub fn add_win(&mut self, player: usize, turns: usize, probability: f64) {
+    self.win_probability[player] += probability;
+
+    if self.lengths.len() <= turns {
+      self.lengths.resize(turns + 1, 0.0);
+    }
+    self.lengths[turns] += probability;
+  }
+
+  // Adds the outcome of the game after one more turn, which happens with `probability`
+  pub fn add_later(&mut self, later: &Outcome, probability: f64) {
+    for (total, p) in self.win_probability.iter_mut().zip(later.win_probability.iter()) {
+      *total += p * probability;
+    }
+
+    if self.lengths.len() < later.lengths.len() + 1 {
+      self.lengths.resize(later.lengths.len() + 1, 0.0);
+    }
+    for (turns, p) in later.lengths.iter().enumerate() {
+      self.lengths[turns + 1] += p * probability;
+    }
+  }
+
+  pub fn expected_turns(&self) -> f64 {
+    self.lengths.iter().enumerate().map(|(turns, p)| turns as f64 * p).sum()
+  }
+
+  pub fn print(&self) {
+    for (i, p) in self.win_probability.iter().enumerate() {
+      println!("  Player {} wins with probability {:.6}", i + 1, p);
+    }
+    println!("  Expected number of turns: {:.3}", self.expected_turns());
+
+    println!("  Game lengths:");
+    for (turns, p) in self.lengths.iter().enumerate().filter(|(_, p)| **p > 0.0) {
+      // Game lengths far off the expected one are too unlikely to show up with 6 decimals
+      if *p < 5e-7 {
+        println!("    {:>4} turns: {:.3e}", turns, p);
+      } else {
+        println!("    {:>4} turns: {:.6}", turns, p);
+      }
+    }
+  }
+}
+
+// Prints player 1's chance of winning and the expected number of turns for every pair of starting
+// squares of a two player game, rows are player 1's square and columns player 2's.
+pub fn print_table<F: FnMut(u32, u32) -> Outcome>(board: u32, mut analyse: F) {
+  let outcomes: Vec<Vec<Outcome>> = (1..=board).map(|one| (1..=board).map(|two| analyse(one, two)).collect()).collect();
+
+  let header: String = (1..=board).map(|two| format!("{:>8}", two)).collect();
+
+  println!("Player 1 win probability:");
+  println!("{:>4}{}", "", header);
+  for (one, row) in outcomes.iter().enumerate() {
+    let cells: String = row.iter().map(|outcome| format!("{:>8.4}", outcome.win_probability[0])).collect();
+    println!("{:>4}{}", one + 1, cells);
+  }
+
+  println!("\nExpected number of turns:");
+  println!("{:>4}{}", "", header);
+  for (one, row) in outcomes.iter().enumerate() {
+    let cells: String = row.iter().map(|outcome| format!("{:>8.2}", outcome.expected_turns())).collect();
+    println!("{:>4}{}", one + 1, cells);
+  }
+}
@@ -0,0 +1,121 @@
+#![allow(dead_code)]
+
+use std::fs;
+
+use crate::rng::Rng;
+
+pub trait Die {
+  fn name(&self) -> String;
+
+  // The next roll, None once the die has nothing left to roll
+  fn cast(&mut self) -> Option<u32>;
+
+  fn number_of_casts(&self) -> u32;
+}
+
+// Rolls 1, 2, ... up to the number of faces and starts over
+pub struct DeterministicDie {
+  val: u32,
+  max: u32,
+
+  number_of_casts: u32,
+}
+
+impl DeterministicDie {
+  pub fn new(max: u32) -> DeterministicDie {
+    DeterministicDie { val: 1, max, number_of_casts: 0 }
+  }
+}
+
+impl Die for DeterministicDie {
+  fn name(&self) -> String {
+    format!("deterministic d{}", self.max)
+  }
+
+  fn cast(&mut self) -> Option<u32> {
+    let val = (self.val - 1) % self.max + 1;
+
+    self.val = val + 1;
+    self.number_of_casts += 1;
+
+    Some(val)
+  }
+
+  fn number_of_casts(&self) -> u32 {
+    self.number_of_casts
+  }
+}
+
+// The same seed always gives the same rolls
+pub struct RandomDie {
+  seed: u64,
+  rng: Rng,
+  max: u32,
+
+  number_of_casts: u32,
+}
+
+impl RandomDie {
+  pub fn new(max: u32, seed: u64) -> RandomDie {
+    RandomDie { seed, rng: Rng::new(seed), max, number_of_casts: 0 }
+  }
+}
+
+impl Die for RandomDie {
+  fn name(&self) -> String {
+    format!("random d{} with seed {}", self.max, self.seed)
+  }
+
+  fn cast(&mut self) -> Option<u32> {
+    self.number_of_casts += 1;
+
+    Some(self.rng.below(self.max as u64) as u32 + 1)
+  }
+
+  fn number_of_casts(&self) -> u32 {
+    self.number_of_casts
+  }
+}
+
+// Rolls recorded in a file, separated by whitespace or commas. Clones replay from the first roll.
+pub struct ReplayedDie {
+  path: String,
+  rolls: Vec<u32>,
+
+  number_of_casts: u32,
+}
+
+impl Clone for ReplayedDie {
+  fn clone(&self) -> ReplayedDie {
+    ReplayedDie { path: self.path.clone(), rolls: self.rolls.clone(), number_of_casts: 0 }
+  }
+}
+
+impl ReplayedDie {
+  pub fn load(path: &str) -> Result<ReplayedDie, String> {
+    let contents = fs::read_to_string(path).map_err(|err| format!("failed to read {}: {}", path, err))?;
+
+    let rolls = contents.split(|c: char| c.is_whitespace() || c == ',').filter(|v| !v.is_empty()).map(|v| {
+      v.parse::<u32>().ok().filter(|roll| *roll > 0).ok_or_else(|| format!("invalid roll {} in {}", v, path))
+    }).collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ReplayedDie { path: path.to_string(), rolls, number_of_casts: 0 })
+  }
+}
+
+impl Die for ReplayedDie {
+  fn name(&self) -> String {
+    format!("{} rolls replayed from {}", self.rolls.len(), self.path)
+  }
+
+  fn cast(&mut self) -> Option<u32> {
+    let roll = self.rolls.get(self.number_of_casts as usize).copied()?;
+    self.number_of_casts += 1;
+
+    Some(roll)
+  }
+
+  fn number_of_casts(&self) -> u32 {
+    self.number_of_casts
+  }
+}
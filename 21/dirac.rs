@@ -0,0 +1,100 @@
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use crate::analysis::Outcome;
+use crate::game::{GameState, Rules};
+
+// Every sum of `rolls` rolls of a die with `faces` faces, with the number of ways to roll it
+pub fn roll_distribution(faces: u32, rolls: u32) -> Vec<(u32, u64)> {
+  // ways[sum] after each roll
+  let mut ways: Vec<u64> = vec![1];
+
+  for _ in 0..rolls {
+    let mut next = vec![0_u64; ways.len() + faces as usize];
+
+    for (sum, count) in ways.iter().enumerate().filter(|(_, count)| **count > 0) {
+      for face in 1..=faces as usize {
+        next[sum + face] += count;
+      }
+    }
+
+    ways = next;
+  }
+
+  ways.into_iter().enumerate().filter(|(_, count)| *count > 0).map(|(sum, count)| (sum as u32, count)).collect()
+}
+
+/// Dirac Dice, where every roll splits the universe in one copy per face
+///
+/// Results are memoised per game state, universes reaching the same positions and scores with the same
+/// player to move have the same outcomes.
+pub struct DiracGame {
+  pub rules: Rules,
+  distribution: Vec<(u32, u64)>,
+  wins: HashMap<GameState, Vec<u128>>,
+  outcomes: HashMap<GameState, Outcome>,
+}
+
+impl DiracGame {
+  pub fn new(rules: Rules) -> DiracGame {
+    DiracGame { rules, distribution: roll_distribution(rules.faces, rules.rolls), wins: HashMap::new(), outcomes: HashMap::new() }
+  }
+
+  pub fn distribution(&self) -> &[(u32, u64)] {
+    &self.distribution
+  }
+
+  pub fn memo_size(&self) -> usize {
+    self.wins.len()
+  }
+
+  // Number of universes won by each player when playing on from `state`
+  pub fn wins(&mut self, state: &GameState) -> Vec<u128> {
+    if let Some(wins) = self.wins.get(state) {
+      return wins.clone();
+    }
+
+    let mut wins = vec![0_u128; state.positions.len()];
+
+    for (steps, count) in self.distribution.clone() {
+      let (next, won) = state.play(&self.rules, steps);
+
+      if won {
+        wins[state.turn] += count as u128;
+      } else {
+        for (total, sub) in wins.iter_mut().zip(self.wins(&next)) {
+          *total += sub * count as u128;
+        }
+      }
+    }
+
+    self.wins.insert(state.clone(), wins.clone());
+    wins
+  }
+
+  // Chances of winning and of each game length when playing on from `state`, every universe is
+  // equally likely
+  pub fn analyse(&mut self, state: &GameState) -> Outcome {
+    if let Some(outcome) = self.outcomes.get(state) {
+      return outcome.clone();
+    }
+
+    let universes: u64 = self.distribution.iter().map(|(_, count)| count).sum();
+    let mut outcome = Outcome::new(state.positions.len());
+
+    for (steps, count) in self.distribution.clone() {
+      let probability = count as f64 / universes as f64;
+      let (next, won) = state.play(&self.rules, steps);
+
+      if won {
+        outcome.add_win(state.turn, 1, probability);
+      } else {
+        outcome.add_later(&self.analyse(&next), probability);
+      }
+    }
+
+    self.outcomes.insert(state.clone(), outcome.clone());
+    outcome
+  }
+}
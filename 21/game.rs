@@ -0,0 +1,64 @@
+#![allow(dead_code)]
+
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub struct Rules {
+  // The die rolls 1 up to and including `faces`
+  pub faces: u32,
+  // Number of times the die is rolled each turn
+  pub rolls: u32,
+  // Squares on the board, numbered 1 up to and including `board`
+  pub board: u32,
+  pub win_score: u32,
+}
+
+impl Default for Rules {
+  fn default() -> Rules {
+    Rules { faces: 3, rolls: 3, board: 10, win_score: 21 }
+  }
+}
+
+impl Rules {
+  // Moves `steps` squares forward from `position`, wrapping around the board
+  pub fn advance(&self, position: u32, steps: u32) -> u32 {
+    (position - 1 + steps) % self.board + 1
+  }
+}
+
+#[derive(Clone,PartialEq,Eq,Hash,Debug)]
+pub struct GameState {
+  pub positions: Vec<u32>,
+  pub scores: Vec<u32>,
+  // Index of the player about to roll
+  pub turn: usize,
+}
+
+impl GameState {
+  pub fn new(positions: &[u32]) -> GameState {
+    GameState { positions: positions.to_vec(), scores: vec![0; positions.len()], turn: 0 }
+  }
+
+  // The state after the current player moves `steps` squares, with whether they won
+  pub fn play(&self, rules: &Rules, steps: u32) -> (GameState, bool) {
+    let mut next = self.clone();
+    let player = self.turn;
+
+    next.positions[player] = rules.advance(next.positions[player], steps);
+    next.scores[player] += next.positions[player];
+    next.turn = (player + 1) % self.positions.len();
+
+    let won = next.scores[player] >= rules.win_score;
+    (next, won)
+  }
+}
+
+// Starting positions from lines like `Player 1 starting position: 4`
+pub fn parse_positions<I: Iterator<Item = String>>(lines: I, rules: &Rules) -> Result<Vec<u32>, String> {
+  lines.filter(|line| !line.trim().is_empty()).map(|line| {
+    let position = line.split_whitespace().last().and_then(|v| v.parse::<u32>().ok());
+
+    match position {
+      Some(position) if position >= 1 && position <= rules.board => Ok(position),
+      _ => Err(format!("expected a starting position from 1 to {}, got {}", rules.board, line)),
+    }
+  }).collect()
+}
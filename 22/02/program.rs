@@ -2,386 +2,206 @@ use std::env;
 use std::fs::File;
 use std::io::{self, BufRead};
 use std::path::Path;
-use std::cmp;
-use std::mem;
+use std::process;
 
-#[derive(Clone,Copy,Hash,PartialEq,Eq)]
-enum State {
-  Off,
-  On,
-}
-
-#[derive(PartialEq,Eq,Hash,Clone,Copy)]
-struct Point {
-  x: i32,
-  y: i32,
-  z: i32,
-}
-
-impl std::fmt::Display for Point {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    write!(f, "({}, {}, {})", self.x, self.y, self.z)
-  }
-}
+#[path = "../cuboid.rs"]
+mod cuboid;
+#[path = "../export.rs"]
+mod export;
+#[path = "../signed_volume.rs"]
+mod signed_volume;
 
-fn min_max(one: &Point, two: &Point) -> (Point, Point) {
-  let Point { x: one_x, y: one_y, z: one_z } = one;
-  let Point { x: two_x, y: two_y, z: two_z } = two;
+use cuboid::{Cuboid, CuboidTree, Point, SimpleCuboid, SplitStrategy, StateCuboid};
 
-  (Point {
-    x: cmp::min(*one_x, *two_x),
-    y: cmp::min(*one_y, *two_y),
-    z: cmp::min(*one_z, *two_z),
-  },
-  Point {
-    x: cmp::max(*one_x, *two_x),
-    y: cmp::max(*one_y, *two_y),
-    z: cmp::max(*one_z, *two_z),
-  })
+struct Options {
+  points: Vec<String>,
+  regions: Vec<String>,
+  engine: String,
+  strategy: SplitStrategy,
+  compact: bool,
+  slice: Option<Vec<i32>>,
+  window: Option<SimpleCuboid<2>>,
+  exports: Vec<String>,
 }
 
-trait Cuboid {
-  fn min(&self) -> Point;
-  fn max(&self) -> Point;
-
-  fn size(&self) -> u64 {
-    let min = self.min();
-    let max = self.max();
-
-    let dx = (max.x - min.x) as u64;
-    let dy = (max.y - min.y) as u64;
-    let dz = (max.z - min.z) as u64;
-
-    dx * dy * dz
-  }
-
-  fn corners(&self) -> [Point; 8] {
-    let Point { x: min_x, y: min_y, z: min_z } = self.min().clone();
-    let Point { x: max_x, y: max_y, z: max_z } = self.max().clone();
-
-    [
-      Point { x: min_x, y: min_y, z: min_z },
-      Point { x: max_x, y: min_y, z: min_z },
-      Point { x: min_x, y: max_y, z: min_z },
-      Point { x: max_x, y: max_y, z: min_z },
-      Point { x: min_x, y: min_y, z: max_z },
-      Point { x: max_x, y: min_y, z: max_z },
-      Point { x: min_x, y: max_y, z: max_z },
-      Point { x: max_x, y: max_y, z: max_z },
-    ]
-  }
-  
-  fn contains_point(&self, point: &Point) -> bool {
-    if point.x < self.min().x || point.x >= self.max().x {
-      false
-    } else if point.y < self.min().y || point.y >= self.max().y {
-      false
-    } else if point.z < self.min().z || point.z >= self.max().z {
-      false
-    } else {
-      true
-    }
-  }
-  
-  fn contains_cuboid(&self, other: &dyn Cuboid) -> bool {
-    if !self.contains_point(&other.min()) {
-      false
-    } else {
-      let max = self.max();
-      let othermax = other.max();
-
-      othermax.x <= max.x && othermax.y <= max.y && othermax.z <= max.z
+fn main() {
+  let mut options = Options {
+    points: Vec::new(),
+    regions: Vec::new(),
+    engine: String::from("tree"),
+    strategy: SplitStrategy::FirstAxis,
+    compact: false,
+    slice: None,
+    window: None,
+    exports: Vec::new(),
+  };
+  let mut files: Vec<String> = Vec::new();
+
+  let mut args = env::args().skip(1);
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "--at" => options.points.push(args.next().expect("Expected a point x,y,z")),
+      "--engine" => options.engine = args.next().expect("Expected an engine"),
+      "--split" => options.strategy = args.next().and_then(|v| SplitStrategy::parse(&v)).expect("Expected a split strategy: first, longest or surface"),
+      "--compact" => options.compact = true,
+      "--region" => options.regions.push(args.next().expect("Expected a region x=a..b,y=c..d,z=e..f")),
+      "--slice" => options.slice = Some(args.next().and_then(|v| parse_coordinates(&v)).expect("Expected the coordinates of the axes after x and y, e.g. a z coordinate")),
+      "--window" => options.window = Some(SimpleCuboid::parse(&args.next().expect("Expected a window x=a..b,y=c..d")).unwrap_or_else(|err| {
+        eprintln!("Invalid window, {}", err);
+        process::exit(1);
+      })),
+      "--export" => options.exports.push(args.next().expect("Expected a path to export to")),
+      _ => files.push(arg),
     }
   }
 
-  fn overlaps(&self, other: &dyn Cuboid) -> bool {
-    if self.min().x >= other.max().x || other.min().x >= self.max().x {
-      false
-    } else if self.min().y >= other.max().y || other.min().y >= self.max().y {
-      false
-    } else if self.min().z >= other.max().x || other.min().x >= self.max().x {
-      false
-    } else {
-      true
-    }
+  if files.len() != 1 {
+    panic!("Expected exactly 1 input file, got {}", files.len());
   }
 
-  fn intersection(&self, other: &dyn Cuboid) -> Option<SimpleCuboid> {
-    let (_, min) = min_max(&self.min(), &other.min());
-    let (max, _) = min_max(&self.max(), &other.max());
+  if let Ok(lines) = read_lines(&files[0]) {
+    let lines: Vec<String> = lines.map(|line| line.unwrap()).collect();
+    let first = lines.first().expect("Expected at least one reboot step");
 
-    if min.x < max.x && min.y < max.y && min.z < max.z {
-      Some(SimpleCuboid{ min: min, max: max })
-    } else {
-      None
+    // The number of ranges in the first step decides the dimension of the reactor
+    match cuboid::dimensions(first) {
+      2 => run::<2>(&lines, &options),
+      3 => run::<3>(&lines, &options),
+      4 => run::<4>(&lines, &options),
+      n => {
+        eprintln!("Unsupported number of dimensions {}, expected 2, 3 or 4", n);
+        process::exit(1);
+      },
     }
+  } else {
+    panic!("Failed to read file");
   }
 }
 
-#[derive(PartialEq,Eq,Hash,Clone,Copy)]
-struct SimpleCuboid {
-  min: Point,
-  max: Point,
-}
-
-impl Cuboid for SimpleCuboid {
-  fn min(&self) -> Point {
-    self.min
-  }
-
-  fn max(&self) -> Point {
-    self.max
-  }
-}
-
-#[derive(PartialEq,Eq,Hash,Clone,Copy)]
-struct StateCuboid {
-  min: Point,
-  max: Point,
-
-  state: State,
-}
-
-impl Cuboid for StateCuboid {
-  fn min(&self) -> Point {
-    self.min
-  }
-
-  fn max(&self) -> Point {
-    self.max
-  }
-}
-
-fn parse_min_max(str: &str) -> (i32, i32) {
-  let mut parts = str[2..].split("..");
-
-  let min = parts.next().unwrap().parse::<i32>().unwrap();
-  let max = parts.next().unwrap().parse::<i32>().unwrap();
-
-  if parts.next() != None {
-    panic!("Invalid line {}", str);
+fn run<const D: usize>(lines: &[String], options: &Options) {
+  let applications: Vec<StateCuboid<D>> = lines.iter().map(|line| {
+    StateCuboid::parse(line.clone()).unwrap_or_else(|err| {
+      eprintln!("Invalid reboot step, {}", err);
+      process::exit(1);
+    })
+  }).collect();
+
+  let points: Vec<Point<D>> = options.points.iter()
+    .map(|point| Point::parse(point).unwrap_or_else(|| panic!("Expected a point with {} coordinates, got {}", D, point)))
+    .collect();
+  let regions: Vec<SimpleCuboid<D>> = options.regions.iter().map(|region| {
+    SimpleCuboid::parse(region).unwrap_or_else(|err| {
+      eprintln!("Invalid region, {}", err);
+      process::exit(1);
+    })
+  }).collect();
+
+  let mut bounds = CuboidTree::bounding(&applications).expect("Expected at least one reboot step").with_strategy(options.strategy);
+  let mut reactors = match cuboid::reactors(&options.engine, &bounds) {
+    Some(reactors) => reactors,
+    None => {
+      eprintln!("Unknown engine {}, expected tree, signed or check", options.engine);
+      process::exit(1);
+    },
+  };
+
+  println!("Total area ranges from {} to {}", bounds.min(), bounds.max());
+  println!("total size: {}", bounds.size());
+
+  for reactor in reactors.iter_mut() {
+    for cuboid in applications.iter() {
+      reactor.apply(cuboid);
+    }
   }
 
-  assert!(min < max);
-
-  (min, max)
-}
-
-impl StateCuboid {
-  fn parse(line: String) -> StateCuboid {
-    if let Some(space_idx) = line.find(' ') {
-      let state = match &line[0..space_idx] {
-        "on" => State::On,
-        _ => State::Off,
-      };
-
-      let coordinates = line[(space_idx + 1)..].split(',').collect::<Vec<_>>();
-      assert_eq!(coordinates.len(), 3);
-
-      let (min_x, max_x) = parse_min_max(coordinates[0]);
-      let (min_y, max_y) = parse_min_max(coordinates[1]);
-      let (min_z, max_z) = parse_min_max(coordinates[2]);
-
-      StateCuboid {
-        state: state,
-        min: Point {
-          x: min_x,
-          y: min_y,
-          z: min_z,
-        },
-        max: Point {
-          x: max_x + 1,
-          y: max_y + 1,
-          z: max_z + 1,
-        },
-      }
-    } else {
-      panic!("Invalid line {}", line);
+  for reactor in reactors.iter() {
+    if reactors.len() > 1 {
+      println!("{}:", reactor.name());
     }
+    reactor.print_stats();
   }
-}
-
-enum CuboidTreeState {
-  Leaf(State),
-  Split(Box<CuboidTree>, Box<CuboidTree>),
-}
 
-/// A binary tree of cuboids
-///
-/// A CuboidTree node has either a state (CuboidTreeState::Leaf) or it has two children
-/// which split the cuboid along the X, Y, or Z axis.
-struct CuboidTree {
-  min: Point,
-  max: Point,
+  if options.compact {
+    let multiple = reactors.len() > 1;
 
-  state: CuboidTreeState,
-}
-
-impl Cuboid for CuboidTree {
-  fn min(&self) -> Point {
-    self.min
-  }
-
-  fn max(&self) -> Point {
-    self.max
-  }
-}
+    for reactor in reactors.iter_mut() {
+      if !reactor.compact() {
+        println!("{} can't be compacted", reactor.name());
+        continue;
+      }
 
-impl CuboidTree {
-  fn size_on(&self) -> u64 {
-    match self.state {
-      CuboidTreeState::Leaf(state) => if state == State::On { self.size() } else { 0 },
-      CuboidTreeState::Split(ref left, ref right) => left.size_on() + right.size_on(),
+      if multiple {
+        println!("{} after compaction:", reactor.name());
+      } else {
+        println!("after compaction:");
+      }
+      reactor.print_stats();
     }
   }
 
-  fn depth(&self) -> usize {
-    match self.state {
-      CuboidTreeState::Leaf(_) => 1,
-      CuboidTreeState::Split(ref left, ref right) => 1 + cmp::max(left.depth(), right.depth()),
-    }
-  }
+  let reactor = &reactors[0];
 
-  fn count_nodes(&self) -> usize {
-    match self.state {
-      CuboidTreeState::Leaf(_) => 1,
-      CuboidTreeState::Split(ref left, ref right) => 1 + left.count_nodes() + right.count_nodes(),
-    }
+  for point in points.iter() {
+    println!("state at {}: {}", point, reactor.state_at(point));
   }
 
-  fn mem_size(&self) -> usize {
-    mem::size_of::<CuboidTree>() + match self.state {
-      CuboidTreeState::Leaf(_) => mem::size_of::<State>(),
-      CuboidTreeState::Split(ref left, ref right) => left.mem_size() + right.mem_size(),
-    }
+  for region in regions.iter() {
+    println!("on in {} to {}: {}", region.min(), region.max(), reactor.count_on_in(region));
   }
 
-  fn apply(&mut self, state: &StateCuboid) -> () {
-    if state.contains_cuboid(self) {
-      // entire self is contained in the state Cuboid, so...
-      self.state = CuboidTreeState::Leaf(state.state);
-      return;
-    }
-
-    if let Some(intersection) = self.intersection(state) {
-      match self.state {
-        CuboidTreeState::Split(ref mut left, ref mut right) => {
-          left.apply(state);
-          right.apply(state);
-        },
-        CuboidTreeState::Leaf(original_state) => {
-          if state.state == original_state {
-            // We already have the right state, so...
-            return;
-          }
-
-          // Find a good point to split the tree further
+  let counts: Vec<u64> = reactors.iter().map(|reactor| reactor.size_on()).collect();
+  assert!(counts.iter().all(|count| *count == counts[0]), "Engines disagree: {:?}", counts);
 
-          let intersection_min = intersection.min();
-          let intersection_max = intersection.max();
+  println!("on: {}", counts[0]);
 
-          let min = self.min();
-          let max = self.max();
-
-          let new_min: Point;
-          let new_max: Point;
-
-          if intersection_min.x > min.x || intersection_max.x < max.x {
-            let cutoff = if intersection_min.x - min.x > max.x - intersection_max.x {
-              intersection_min.x
-            } else {
-              intersection_max.x
-            };
-
-            new_min = Point { x: cutoff, y: min.y, z: min.z };
-            new_max = Point { x: cutoff, y: max.y, z: max.z };
-          } else if intersection_min.y > min.y || intersection_max.y < max.y {
-            let cutoff = if intersection_min.y - min.y > max.y - intersection_max.y {
-              intersection_min.y
-            } else {
-              intersection_max.y
-            };
-
-            new_min = Point { x: min.x, y: cutoff, z: min.z };
-            new_max = Point { x: max.x, y: cutoff, z: max.z };
-          } else if intersection_min.z > min.z || intersection_max.z < max.z {
-            let cutoff = if intersection_min.z - min.z > max.z - intersection_max.z {
-              intersection_min.z
-            } else {
-              intersection_max.z
-            };
-
-            new_min = Point { x: min.x, y: min.y, z: cutoff };
-            new_max = Point { x: max.x, y: max.y, z: cutoff };
-          } else {
-            panic!("couldn't find point to cut");
-          }
-
-          let mut left = CuboidTree {
-            min: min,
-            max: new_max,
-  
-            state: CuboidTreeState::Leaf(original_state),
-          };
-          left.apply(state);
-
-          let mut right = CuboidTree {
-            min: new_min,
-            max: max,
-  
-            state: CuboidTreeState::Leaf(original_state),
-          };
-          right.apply(state);
-  
-          self.state = CuboidTreeState::Split(
-            Box::new(left),
-            Box::new(right),
-          );
+  if options.slice.is_some() || !options.exports.is_empty() {
+    // Reuse the tree engine's tree, only the signed volumes engine needs one built for the export
+    match reactors.iter().find_map(|reactor| reactor.as_tree()) {
+      Some(tree) => export(tree, options),
+      None => {
+        for cuboid in applications.iter() {
+          bounds.apply(cuboid);
         }
-      }
+        if options.compact {
+          bounds.compact();
+        }
+
+        export(&bounds, options);
+      },
     }
   }
 }
 
-fn main() {
-  let args: Vec<String> = env::args().collect();
-
-  if args.len() != 2 {
-    panic!("Expected exactly 1 argument, got {}", args.len());
+// Comma separated coordinates, the empty string is no coordinates at all
+fn parse_coordinates(str: &str) -> Option<Vec<i32>> {
+  if str.trim().is_empty() {
+    return Some(Vec::new());
   }
 
-  if let Ok(lines) = read_lines(&args[1]) {
-    let applications: Vec<_> = lines.map(|line| {
-      StateCuboid::parse(line.unwrap())
-    }).collect();
-
-    let min = applications.iter().map(|cuboid| cuboid.min()).reduce(|a, b| {
-      let (min, _) = min_max(&a, &b);
-      min
-    }).unwrap();
-    let max = applications.iter().map(|cuboid| cuboid.max()).reduce(|a, b| {
-      let (_, max) = min_max(&a, &b);
-      max
-    }).unwrap();
-
-    println!("Total area ranges from {} to {}", min, max);
+  str.split(',').map(|part| part.trim().parse::<i32>().ok()).collect()
+}
 
-    let mut state = CuboidTree {
-      min: min,
-      max: max,
-      state: CuboidTreeState::Leaf(State::Off),
-    };
+// Writes the requested slice and exports of `tree`
+fn export<const D: usize>(tree: &CuboidTree<D>, options: &Options) {
+  let slice = options.slice.as_ref().map(|fixed| {
+    export::Slice::new(tree, fixed, options.window.as_ref()).unwrap_or_else(|err| {
+      eprintln!("Invalid slice, {}", err);
+      process::exit(1);
+    })
+  });
 
-    for cuboid in applications {
-      state.apply(&cuboid);
+  for path in options.exports.iter() {
+    if let Err(err) = export::export(path, tree, slice.as_ref()) {
+      eprintln!("Failed to export {}: {}", path, err);
+      process::exit(1);
     }
+  }
 
-    println!("total size: {}", state.size());
-    println!("max depth: {}", state.depth());
-    println!("number of nodes in tree: {} (memory usage: {} bytes)", state.count_nodes(), state.mem_size());
-    println!("on: {}", state.size_on());
-  } else {
-    panic!("Failed to read file");
+  // Print the slice unless it went to a bitmap
+  if let Some(slice) = slice.as_ref() {
+    if !options.exports.iter().any(|path| path.to_lowercase().ends_with(".pbm")) {
+      export::write_ascii(&mut io::stdout().lock(), tree, slice).unwrap();
+    }
   }
 }
 
@@ -391,4 +211,4 @@ fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
 where P: AsRef<Path>, {
     let file = File::open(filename)?;
     Ok(io::BufReader::new(file).lines())
-}
\ No newline at end of file
+}
@@ -0,0 +1,606 @@
+#![allow(dead_code)]
+
+use std::cmp;
+use std::mem;
+
+use crate::signed_volume::SignedVolumes;
+
+#[derive(Clone,Copy,Hash,PartialEq,Eq)]
+pub enum State {
+  Off,
+  On,
+}
+
+// A point in D-dimensional space, axis 0, 1 and 2 are called x, y and z.
+#[derive(PartialEq,Eq,Hash,Clone,Copy)]
+pub struct Point<const D: usize = 3> {
+  pub coords: [i32; D],
+}
+
+impl<const D: usize> std::fmt::Display for Point<D> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let coords: Vec<String> = self.coords.iter().map(|c| c.to_string()).collect();
+    write!(f, "({})", coords.join(", "))
+  }
+}
+
+impl std::fmt::Display for State {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      State::Off => write!(f, "off"),
+      State::On => write!(f, "on"),
+    }
+  }
+}
+
+pub fn min_max<const D: usize>(one: &Point<D>, two: &Point<D>) -> (Point<D>, Point<D>) {
+  let mut min = *one;
+  let mut max = *one;
+
+  for axis in 0..D {
+    min.coords[axis] = cmp::min(one.coords[axis], two.coords[axis]);
+    max.coords[axis] = cmp::max(one.coords[axis], two.coords[axis]);
+  }
+
+  (min, max)
+}
+
+// An axis-aligned box in D dimensions, `min` is inclusive and `max` exclusive.
+pub trait Cuboid<const D: usize = 3> {
+  fn min(&self) -> Point<D>;
+  fn max(&self) -> Point<D>;
+
+  fn size(&self) -> u64 {
+    let min = self.min();
+    let max = self.max();
+
+    (0..D).map(|axis| (max.coords[axis] - min.coords[axis]) as u64).product()
+  }
+
+  fn corners(&self) -> Vec<Point<D>> {
+    let min = self.min();
+    let max = self.max();
+
+    (0..(1_usize << D)).map(|corner| {
+      let mut point = min;
+      for axis in 0..D {
+        if corner & (1 << axis) != 0 {
+          point.coords[axis] = max.coords[axis];
+        }
+      }
+      point
+    }).collect()
+  }
+
+  fn contains_point(&self, point: &Point<D>) -> bool {
+    let min = self.min();
+    let max = self.max();
+
+    (0..D).all(|axis| point.coords[axis] >= min.coords[axis] && point.coords[axis] < max.coords[axis])
+  }
+
+  fn contains_cuboid(&self, other: &dyn Cuboid<D>) -> bool {
+    if !self.contains_point(&other.min()) {
+      false
+    } else {
+      let max = self.max();
+      let othermax = other.max();
+
+      (0..D).all(|axis| othermax.coords[axis] <= max.coords[axis])
+    }
+  }
+
+  fn overlaps(&self, other: &dyn Cuboid<D>) -> bool {
+    let (min, max) = (self.min(), self.max());
+    let (other_min, other_max) = (other.min(), other.max());
+
+    (0..D).all(|axis| min.coords[axis] < other_max.coords[axis] && other_min.coords[axis] < max.coords[axis])
+  }
+
+  fn intersection(&self, other: &dyn Cuboid<D>) -> Option<SimpleCuboid<D>> {
+    let (_, min) = min_max(&self.min(), &other.min());
+    let (max, _) = min_max(&self.max(), &other.max());
+
+    if (0..D).all(|axis| min.coords[axis] < max.coords[axis]) {
+      Some(SimpleCuboid { min, max })
+    } else {
+      None
+    }
+  }
+}
+
+#[derive(PartialEq,Eq,Hash,Clone,Copy)]
+pub struct SimpleCuboid<const D: usize = 3> {
+  pub min: Point<D>,
+  pub max: Point<D>,
+}
+
+impl<const D: usize> Cuboid<D> for SimpleCuboid<D> {
+  fn min(&self) -> Point<D> {
+    self.min
+  }
+
+  fn max(&self) -> Point<D> {
+    self.max
+  }
+}
+
+#[derive(PartialEq,Eq,Hash,Clone,Copy)]
+pub struct StateCuboid<const D: usize = 3> {
+  pub min: Point<D>,
+  pub max: Point<D>,
+
+  pub state: State,
+}
+
+impl<const D: usize> Cuboid<D> for StateCuboid<D> {
+  fn min(&self) -> Point<D> {
+    self.min
+  }
+
+  fn max(&self) -> Point<D> {
+    self.max
+  }
+}
+
+// Parses `x=a..b`, the name of the axis is ignored. The range is inclusive, so `x=a..a` is a single cell.
+fn parse_min_max(str: &str) -> Result<(i32, i32), String> {
+  let (_, range) = str.split_once('=').ok_or_else(|| format!("invalid range {}", str))?;
+  let (min, max) = range.split_once("..").ok_or_else(|| format!("invalid range {}", str))?;
+
+  let min = min.trim().parse::<i32>().map_err(|_| format!("invalid range {}", str))?;
+  let max = max.trim().parse::<i32>().map_err(|_| format!("invalid range {}", str))?;
+
+  if min > max {
+    return Err(format!("range {} ends before it starts", str));
+  }
+
+  Ok((min, max))
+}
+
+// Number of dimensions of a reboot step like `on x=a..b,y=c..d`
+pub fn dimensions(line: &str) -> usize {
+  line.split(',').count()
+}
+
+impl<const D: usize> SimpleCuboid<D> {
+  // Parses `x=a..b,y=c..d,z=e..f` with one range per dimension, the ranges are inclusive.
+  pub fn parse(str: &str) -> Result<SimpleCuboid<D>, String> {
+    let coordinates = str.split(',').collect::<Vec<_>>();
+    if coordinates.len() != D {
+      return Err(format!("expected {} ranges in {}", D, str));
+    }
+
+    let mut min = Point { coords: [0; D] };
+    let mut max = Point { coords: [0; D] };
+
+    for (axis, coordinate) in coordinates.into_iter().enumerate() {
+      let (axis_min, axis_max) = parse_min_max(coordinate)?;
+
+      min.coords[axis] = axis_min;
+      max.coords[axis] = axis_max + 1;
+    }
+
+    Ok(SimpleCuboid { min, max })
+  }
+}
+
+impl<const D: usize> Point<D> {
+  pub fn axis(&self, axis: usize) -> i32 {
+    self.coords[axis]
+  }
+
+  pub fn with_axis(&self, axis: usize, value: i32) -> Point<D> {
+    let mut point = *self;
+    point.coords[axis] = value;
+    point
+  }
+
+  // Parses comma separated coordinates, e.g. `x,y,z`
+  pub fn parse(str: &str) -> Option<Point<D>> {
+    let parts = str.split(',').map(|part| part.trim().parse::<i32>().ok()).collect::<Option<Vec<_>>>()?;
+
+    Some(Point { coords: parts.try_into().ok()? })
+  }
+}
+
+impl<const D: usize> StateCuboid<D> {
+  pub fn parse(line: String) -> Result<StateCuboid<D>, String> {
+    if let Some(space_idx) = line.find(' ') {
+      let state = match &line[0..space_idx] {
+        "on" => State::On,
+        _ => State::Off,
+      };
+
+      let SimpleCuboid { min, max } = SimpleCuboid::parse(&line[(space_idx + 1)..])?;
+
+      Ok(StateCuboid { state, min, max })
+    } else {
+      Err(format!("invalid line {}", line))
+    }
+  }
+}
+
+// The state of the reactor after a number of reboot steps
+pub trait Reactor<const D: usize = 3> {
+  fn name(&self) -> &'static str;
+
+  fn apply(&mut self, step: &StateCuboid<D>);
+
+  fn size_on(&self) -> u64;
+
+  fn count_on_in(&self, query: &dyn Cuboid<D>) -> u64;
+
+  fn state_at(&self, point: &Point<D>) -> State {
+    let mut max = *point;
+    for coord in max.coords.iter_mut() {
+      *coord += 1;
+    }
+    let cube = SimpleCuboid { min: *point, max };
+
+    if self.count_on_in(&cube) > 0 { State::On } else { State::Off }
+  }
+
+  fn mem_size(&self) -> usize;
+
+  // Merges what can be merged, returns false if the reactor has no way to compact itself
+  fn compact(&mut self) -> bool {
+    false
+  }
+
+  fn print_stats(&self) {
+    println!("memory usage: {} bytes", self.mem_size());
+  }
+
+  // The reactor as a tree, for the queries only a tree can answer
+  fn as_tree(&self) -> Option<&CuboidTree<D>> {
+    None
+  }
+}
+
+// Creates the reactors for the given engine: "tree", "signed", or "check" for both
+pub fn reactors<const D: usize>(engine: &str, bounds: &CuboidTree<D>) -> Option<Vec<Box<dyn Reactor<D>>>> {
+  let tree = || Box::new(CuboidTree::new(bounds.min, bounds.max).with_strategy(bounds.strategy)) as Box<dyn Reactor<D>>;
+  let signed = || Box::new(SignedVolumes::<D>::new()) as Box<dyn Reactor<D>>;
+
+  match engine {
+    "tree" => Some(vec![tree()]),
+    "signed" => Some(vec![signed()]),
+    "check" => Some(vec![tree(), signed()]),
+    _ => None,
+  }
+}
+
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum SplitStrategy {
+  // Cut along the first axis where the node sticks out of the intersection
+  FirstAxis,
+  // Cut along the axis where the node is longest
+  LongestAxis,
+  // Pick the cut that leaves the smallest surface area on the side that still needs splitting
+  SurfaceArea,
+}
+
+impl SplitStrategy {
+  pub fn parse(str: &str) -> Option<SplitStrategy> {
+    match str {
+      "first" => Some(SplitStrategy::FirstAxis),
+      "longest" => Some(SplitStrategy::LongestAxis),
+      "surface" => Some(SplitStrategy::SurfaceArea),
+      _ => None,
+    }
+  }
+
+  // Returns the axis and coordinate to split `node` at, given the part that needs to change state.
+  fn choose_cut<const D: usize>(&self, node: &dyn Cuboid<D>, intersection: &dyn Cuboid<D>) -> (usize, i32) {
+    let (min, max) = (node.min(), node.max());
+    let (intersection_min, intersection_max) = (intersection.min(), intersection.max());
+
+    // Cuts that separate part of the node from the intersection, with the size of the slab that gets cut off
+    let mut candidates: Vec<(usize, i32, i64)> = Vec::new();
+    for axis in 0..D {
+      if intersection_min.axis(axis) > min.axis(axis) {
+        candidates.push((axis, intersection_min.axis(axis), (intersection_min.axis(axis) - min.axis(axis)) as i64));
+      }
+      if intersection_max.axis(axis) < max.axis(axis) {
+        candidates.push((axis, intersection_max.axis(axis), (max.axis(axis) - intersection_max.axis(axis)) as i64));
+      }
+    }
+
+    if candidates.is_empty() {
+      panic!("couldn't find point to cut");
+    }
+
+    // Prefer cutting off the thickest slab along the chosen axis
+    let thickest_on = |axis: usize| -> (usize, i32) {
+      let (axis, cutoff, _) = *candidates.iter()
+        .filter(|candidate| candidate.0 == axis)
+        .reduce(|a, b| if b.2 >= a.2 { b } else { a })
+        .unwrap();
+
+      (axis, cutoff)
+    };
+
+    match self {
+      SplitStrategy::FirstAxis => thickest_on(candidates[0].0),
+      SplitStrategy::LongestAxis => {
+        let axis = candidates.iter()
+          .map(|candidate| candidate.0)
+          .max_by_key(|axis| (max.axis(*axis) - min.axis(*axis), cmp::Reverse(*axis)))
+          .unwrap();
+
+        thickest_on(axis)
+      },
+      SplitStrategy::SurfaceArea => {
+        let remaining_area = |axis: usize, cutoff: i32| -> i64 {
+          let mut extents = [0_i64; D];
+          for (i, extent) in extents.iter_mut().enumerate() {
+            *extent = (max.axis(i) - min.axis(i)) as i64;
+          }
+
+          extents[axis] = if cutoff == intersection_min.axis(axis) {
+            (max.axis(axis) - cutoff) as i64
+          } else {
+            (cutoff - min.axis(axis)) as i64
+          };
+
+          // Sum of the sizes of the faces, leaving out one axis at a time
+          (0..D).map(|skip| (0..D).filter(|i| *i != skip).map(|i| extents[i]).product::<i64>()).sum()
+        };
+
+        let (axis, cutoff, _) = *candidates.iter().min_by_key(|(axis, cutoff, _)| remaining_area(*axis, *cutoff)).unwrap();
+        (axis, cutoff)
+      },
+    }
+  }
+}
+
+pub enum CuboidTreeState<const D: usize = 3> {
+  Leaf(State),
+  Split(Box<CuboidTree<D>>, Box<CuboidTree<D>>),
+}
+
+/// A binary tree of cuboids
+///
+/// A CuboidTree node has either a state (CuboidTreeState::Leaf) or it has two children
+/// which split the cuboid along one of its D axes.
+pub struct CuboidTree<const D: usize = 3> {
+  pub min: Point<D>,
+  pub max: Point<D>,
+
+  pub state: CuboidTreeState<D>,
+  pub strategy: SplitStrategy,
+}
+
+impl<const D: usize> Cuboid<D> for CuboidTree<D> {
+  fn min(&self) -> Point<D> {
+    self.min
+  }
+
+  fn max(&self) -> Point<D> {
+    self.max
+  }
+}
+
+impl<const D: usize> CuboidTree<D> {
+  pub fn new(min: Point<D>, max: Point<D>) -> CuboidTree<D> {
+    CuboidTree { min, max, state: CuboidTreeState::Leaf(State::Off), strategy: SplitStrategy::FirstAxis }
+  }
+
+  pub fn with_strategy(mut self, strategy: SplitStrategy) -> CuboidTree<D> {
+    self.strategy = strategy;
+    self
+  }
+
+  // Collapses split nodes whose children ended up as leaves with the same state.
+  pub fn compact(&mut self) {
+    if let CuboidTreeState::Split(ref mut left, ref mut right) = self.state {
+      left.compact();
+      right.compact();
+
+      if let (CuboidTreeState::Leaf(left_state), CuboidTreeState::Leaf(right_state)) = (&left.state, &right.state) {
+        if left_state == right_state {
+          self.state = CuboidTreeState::Leaf(*left_state);
+        }
+      }
+    }
+  }
+
+  // Smallest tree that covers all of the given cuboids
+  pub fn bounding(cuboids: &[StateCuboid<D>]) -> Option<CuboidTree<D>> {
+    let min = cuboids.iter().map(|cuboid| cuboid.min()).reduce(|a, b| min_max(&a, &b).0)?;
+    let max = cuboids.iter().map(|cuboid| cuboid.max()).reduce(|a, b| min_max(&a, &b).1)?;
+
+    Some(CuboidTree::new(min, max))
+  }
+
+  pub fn state_at(&self, point: &Point<D>) -> State {
+    let mut node = self;
+
+    if !node.contains_point(point) {
+      return State::Off;
+    }
+
+    loop {
+      match node.state {
+        CuboidTreeState::Leaf(state) => return state,
+        CuboidTreeState::Split(ref left, ref right) => {
+          node = if left.contains_point(point) { left } else { right };
+        },
+      }
+    }
+  }
+
+  pub fn count_on_in(&self, query: &dyn Cuboid<D>) -> u64 {
+    let intersection = match self.intersection(query) {
+      Some(intersection) => intersection,
+      None => return 0,
+    };
+
+    match self.state {
+      CuboidTreeState::Leaf(state) => if state == State::On { intersection.size() } else { 0 },
+      CuboidTreeState::Split(ref left, ref right) => left.count_on_in(query) + right.count_on_in(query),
+    }
+  }
+
+  // Iterates over the maximal on cuboids of the tree, left to right. A subtree that is on everywhere comes
+  // out as its own cuboid rather than as its leaves, whether or not the tree was compacted.
+  pub fn on_leaves(&self) -> OnLeaves<'_, D> {
+    OnLeaves { stack: vec![self] }
+  }
+
+  fn all_on(&self) -> bool {
+    match self.state {
+      CuboidTreeState::Leaf(state) => state == State::On,
+      CuboidTreeState::Split(ref left, ref right) => left.all_on() && right.all_on(),
+    }
+  }
+
+  pub fn size_on(&self) -> u64 {
+    match self.state {
+      CuboidTreeState::Leaf(state) => if state == State::On { self.size() } else { 0 },
+      CuboidTreeState::Split(ref left, ref right) => left.size_on() + right.size_on(),
+    }
+  }
+
+  pub fn depth(&self) -> usize {
+    match self.state {
+      CuboidTreeState::Leaf(_) => 1,
+      CuboidTreeState::Split(ref left, ref right) => 1 + cmp::max(left.depth(), right.depth()),
+    }
+  }
+
+  pub fn count_nodes(&self) -> usize {
+    match self.state {
+      CuboidTreeState::Leaf(_) => 1,
+      CuboidTreeState::Split(ref left, ref right) => 1 + left.count_nodes() + right.count_nodes(),
+    }
+  }
+
+  pub fn mem_size(&self) -> usize {
+    mem::size_of::<CuboidTree<D>>() + match self.state {
+      CuboidTreeState::Leaf(_) => mem::size_of::<State>(),
+      CuboidTreeState::Split(ref left, ref right) => left.mem_size() + right.mem_size(),
+    }
+  }
+
+  pub fn apply(&mut self, state: &StateCuboid<D>) {
+    if state.contains_cuboid(self) {
+      // entire self is contained in the state Cuboid, so...
+      self.state = CuboidTreeState::Leaf(state.state);
+      return;
+    }
+
+    if let Some(intersection) = self.intersection(state) {
+      match self.state {
+        CuboidTreeState::Split(ref mut left, ref mut right) => {
+          left.apply(state);
+          right.apply(state);
+        },
+        CuboidTreeState::Leaf(original_state) => {
+          if state.state == original_state {
+            // We already have the right state, so...
+            return;
+          }
+
+          // Find a good point to split the tree further
+          let (axis, cutoff) = self.strategy.choose_cut(self, &intersection);
+
+          let min = self.min();
+          let max = self.max();
+          let new_min = min.with_axis(axis, cutoff);
+          let new_max = max.with_axis(axis, cutoff);
+
+          let mut left = CuboidTree {
+            min,
+            max: new_max,
+
+            state: CuboidTreeState::Leaf(original_state),
+            strategy: self.strategy,
+          };
+          left.apply(state);
+
+          let mut right = CuboidTree {
+            min: new_min,
+            max,
+
+            state: CuboidTreeState::Leaf(original_state),
+            strategy: self.strategy,
+          };
+          right.apply(state);
+  
+          self.state = CuboidTreeState::Split(
+            Box::new(left),
+            Box::new(right),
+          );
+        }
+      }
+    }
+  }
+}
+
+pub struct OnLeaves<'a, const D: usize = 3> {
+  stack: Vec<&'a CuboidTree<D>>,
+}
+
+impl<'a, const D: usize> Iterator for OnLeaves<'a, D> {
+  type Item = SimpleCuboid<D>;
+
+  fn next(&mut self) -> Option<SimpleCuboid<D>> {
+    while let Some(node) = self.stack.pop() {
+      if node.all_on() {
+        return Some(SimpleCuboid { min: node.min, max: node.max });
+      }
+
+      match node.state {
+        CuboidTreeState::Leaf(_) => {},
+        CuboidTreeState::Split(ref left, ref right) => {
+          self.stack.push(right);
+          self.stack.push(left);
+        },
+      }
+    }
+
+    None
+  }
+}
+
+impl<const D: usize> Reactor<D> for CuboidTree<D> {
+  fn name(&self) -> &'static str {
+    "cuboid tree"
+  }
+
+  fn apply(&mut self, step: &StateCuboid<D>) {
+    CuboidTree::apply(self, step)
+  }
+
+  fn size_on(&self) -> u64 {
+    CuboidTree::size_on(self)
+  }
+
+  fn count_on_in(&self, query: &dyn Cuboid<D>) -> u64 {
+    CuboidTree::count_on_in(self, query)
+  }
+
+  fn state_at(&self, point: &Point<D>) -> State {
+    CuboidTree::state_at(self, point)
+  }
+
+  fn compact(&mut self) -> bool {
+    CuboidTree::compact(self);
+    true
+  }
+
+  fn mem_size(&self) -> usize {
+    CuboidTree::mem_size(self)
+  }
+
+  fn print_stats(&self) {
+    println!("max depth: {}", self.depth());
+    println!("number of nodes in tree: {} (memory usage: {} bytes)", self.count_nodes(), self.mem_size());
+  }
+
+  fn as_tree(&self) -> Option<&CuboidTree<D>> {
+    Some(self)
+  }
+}
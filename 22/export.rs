@@ -0,0 +1,131 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::cuboid::{Cuboid, CuboidTree, SimpleCuboid};
+
+// Refuse to render slices larger than this many cells, use a smaller window instead
+const MAX_SLICE_CELLS: u64 = 1 << 26;
+
+// A 2D cut through the reactor along the x and y axes, every other axis is fixed to a single coordinate.
+pub struct Slice<const D: usize> {
+  window: SimpleCuboid<D>,
+}
+
+impl<const D: usize> Slice<D> {
+  // `fixed` holds the coordinates of the axes after x and y, `window` limits the x and y range.
+  pub fn new(tree: &CuboidTree<D>, fixed: &[i32], window: Option<&SimpleCuboid<2>>) -> Result<Slice<D>, String> {
+    if D < 2 {
+      return Err(format!("Can't slice a {}-dimensional reactor", D));
+    }
+    if fixed.len() != D - 2 {
+      return Err(format!("Expected {} slice coordinates, got {}", D - 2, fixed.len()));
+    }
+
+    let mut min = tree.min();
+    let mut max = tree.max();
+
+    if let Some(window) = window {
+      for axis in 0..2 {
+        min.coords[axis] = window.min.axis(axis);
+        max.coords[axis] = window.max.axis(axis);
+      }
+    }
+
+    for (axis, coord) in fixed.iter().enumerate().map(|(i, c)| (i + 2, *c)) {
+      min.coords[axis] = coord;
+      max.coords[axis] = coord + 1;
+    }
+
+    let window = SimpleCuboid { min, max };
+    if window.size() > MAX_SLICE_CELLS {
+      return Err(format!("Slice from {} to {} is too large to render, narrow it down with --window", min, max));
+    }
+
+    Ok(Slice { window })
+  }
+
+  fn width(&self) -> usize {
+    (self.window.max.axis(0) - self.window.min.axis(0)) as usize
+  }
+
+  fn height(&self) -> usize {
+    (self.window.max.axis(1) - self.window.min.axis(1)) as usize
+  }
+
+  // Rows of cells from the lowest y up, true where the reactor is on
+  pub fn render(&self, tree: &CuboidTree<D>) -> Vec<Vec<bool>> {
+    let mut cells = vec![vec![false; self.width()]; self.height()];
+    let min = self.window.min;
+
+    for leaf in tree.on_leaves() {
+      if let Some(part) = leaf.intersection(&self.window) {
+        for row in cells[(part.min.axis(1) - min.axis(1)) as usize..(part.max.axis(1) - min.axis(1)) as usize].iter_mut() {
+          for cell in row[(part.min.axis(0) - min.axis(0)) as usize..(part.max.axis(0) - min.axis(0)) as usize].iter_mut() {
+            *cell = true;
+          }
+        }
+      }
+    }
+
+    cells
+  }
+}
+
+pub fn write_ascii<W: Write, const D: usize>(out: &mut W, tree: &CuboidTree<D>, slice: &Slice<D>) -> io::Result<()> {
+  for row in slice.render(tree) {
+    let line: String = row.iter().map(|on| if *on { '#' } else { '.' }).collect();
+    writeln!(out, "{}", line)?;
+  }
+
+  Ok(())
+}
+
+// Plain (P1) portable bitmap, on cubes are black
+pub fn write_pbm<W: Write, const D: usize>(out: &mut W, tree: &CuboidTree<D>, slice: &Slice<D>) -> io::Result<()> {
+  writeln!(out, "P1")?;
+  writeln!(out, "# advent of code 2021 day 22 slice from {} to {}", slice.window.min, slice.window.max)?;
+  writeln!(out, "{} {}", slice.width(), slice.height())?;
+
+  for row in slice.render(tree) {
+    let line: Vec<&str> = row.iter().map(|on| if *on { "1" } else { "0" }).collect();
+    writeln!(out, "{}", line.join(" "))?;
+  }
+
+  Ok(())
+}
+
+// One line per on leaf of the tree with the minimum corner followed by the maximum corner. Boxes don't
+// overlap, so their sizes add up to the number of cubes that are on.
+pub fn write_boxes<W: Write, const D: usize>(out: &mut W, tree: &CuboidTree<D>) -> io::Result<()> {
+  writeln!(out, "# advent of code 2021 day 22 on cuboids")?;
+  writeln!(out, "# {} dimensions, min inclusive, max exclusive", D)?;
+
+  for leaf in tree.on_leaves() {
+    let coords: Vec<String> = leaf.min.coords.iter().chain(leaf.max.coords.iter()).map(|c| c.to_string()).collect();
+    writeln!(out, "{}", coords.join(" "))?;
+  }
+
+  Ok(())
+}
+
+// Picks the format based on the file extension, bitmaps need a slice.
+pub fn export<const D: usize>(path: &str, tree: &CuboidTree<D>, slice: Option<&Slice<D>>) -> io::Result<()> {
+  let extension = Path::new(path).extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase());
+  let invalid = |message: String| Err(io::Error::new(io::ErrorKind::InvalidInput, message));
+
+  match (extension.as_deref(), slice) {
+    (Some("txt"), _) => {
+      let mut out = BufWriter::new(File::create(path)?);
+      write_boxes(&mut out, tree)?;
+      out.flush()
+    },
+    (Some("pbm"), Some(slice)) => {
+      let mut out = BufWriter::new(File::create(path)?);
+      write_pbm(&mut out, tree, slice)?;
+      out.flush()
+    },
+    (Some("pbm"), None) => invalid(format!("Exporting {} needs a slice, pass --slice", path)),
+    _ => invalid(format!("Unknown export format for {}, expected .txt or .pbm", path)),
+  }
+}
@@ -0,0 +1,70 @@
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::mem;
+
+use crate::cuboid::{Cuboid, Reactor, SimpleCuboid, State, StateCuboid};
+
+// Tracks the reactor as a list of cuboids with a signed multiplicity, following inclusion–exclusion:
+// the number of cubes that are on is the sum of each cuboid's size times its multiplicity.
+pub struct SignedVolumes<const D: usize = 3> {
+  cuboids: HashMap<SimpleCuboid<D>, i64>,
+}
+
+impl<const D: usize> SignedVolumes<D> {
+  pub fn new() -> SignedVolumes<D> {
+    SignedVolumes { cuboids: HashMap::new() }
+  }
+
+  pub fn len(&self) -> usize {
+    self.cuboids.len()
+  }
+}
+
+impl<const D: usize> Reactor<D> for SignedVolumes<D> {
+  fn name(&self) -> &'static str {
+    "signed volumes"
+  }
+
+  fn apply(&mut self, step: &StateCuboid<D>) {
+    let mut changes: HashMap<SimpleCuboid<D>, i64> = HashMap::new();
+
+    // Cancel out whatever is counted inside the step, so it ends up off
+    for (cuboid, count) in self.cuboids.iter() {
+      if let Some(intersection) = cuboid.intersection(step) {
+        *changes.entry(intersection).or_insert(0) -= count;
+      }
+    }
+
+    if step.state == State::On {
+      *changes.entry(SimpleCuboid { min: step.min, max: step.max }).or_insert(0) += 1;
+    }
+
+    for (cuboid, change) in changes {
+      let count = self.cuboids.entry(cuboid).or_insert(0);
+      *count += change;
+
+      if *count == 0 {
+        self.cuboids.remove(&cuboid);
+      }
+    }
+  }
+
+  fn count_on_in(&self, query: &dyn Cuboid<D>) -> u64 {
+    let total: i128 = self.cuboids.iter()
+      .filter_map(|(cuboid, count)| cuboid.intersection(query).map(|i| i.size() as i128 * *count as i128))
+      .sum();
+
+    total as u64
+  }
+
+  fn size_on(&self) -> u64 {
+    let total: i128 = self.cuboids.iter().map(|(cuboid, count)| cuboid.size() as i128 * *count as i128).sum();
+
+    total as u64
+  }
+
+  fn mem_size(&self) -> usize {
+    mem::size_of::<SignedVolumes<D>>() + self.cuboids.capacity() * mem::size_of::<(SimpleCuboid<D>, i64)>()
+  }
+}
@@ -0,0 +1,256 @@
+#![allow(dead_code)]
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+
+const BASE_BITS: u32 = 32;
+
+// Little-endian 32-bit limbs, no trailing zero limbs. Zero is the empty vector.
+#[derive(Clone,PartialEq,Eq,Hash,Debug,Default)]
+pub struct BigUint {
+  limbs: Vec<u32>,
+}
+
+impl BigUint {
+  pub fn zero() -> BigUint {
+    BigUint { limbs: Vec::new() }
+  }
+
+  pub fn one() -> BigUint {
+    BigUint::from(1_u64)
+  }
+
+  pub fn is_zero(&self) -> bool {
+    self.limbs.is_empty()
+  }
+
+  pub fn bits(&self) -> u64 {
+    match self.limbs.last() {
+      None => 0,
+      Some(top) => (self.limbs.len() as u64 - 1) * BASE_BITS as u64 + (BASE_BITS - top.leading_zeros()) as u64,
+    }
+  }
+
+  pub fn to_u64(&self) -> Option<u64> {
+    match self.limbs.len() {
+      0 => Some(0),
+      1 => Some(self.limbs[0] as u64),
+      2 => Some(self.limbs[0] as u64 | ((self.limbs[1] as u64) << BASE_BITS)),
+      _ => None,
+    }
+  }
+
+  // Shifts left by `n` bits (n < 32) and ors in `value`, which must fit in those `n` bits.
+  pub fn push_bits(&mut self, n: u32, value: u32) {
+    assert!(n < BASE_BITS);
+    assert_eq!(value >> n, 0);
+
+    let mut carry = value;
+    for limb in self.limbs.iter_mut() {
+      let shifted = ((*limb as u64) << n) | carry as u64;
+      *limb = shifted as u32;
+      carry = (shifted >> BASE_BITS) as u32;
+    }
+
+    if carry != 0 {
+      self.limbs.push(carry);
+    }
+  }
+
+  fn trim(&mut self) {
+    while self.limbs.last() == Some(&0) {
+      self.limbs.pop();
+    }
+  }
+
+  // Divides in place by a small divisor, returning the remainder.
+  fn div_rem_small(&mut self, divisor: u32) -> u32 {
+    let mut remainder = 0_u64;
+    for limb in self.limbs.iter_mut().rev() {
+      let current = (remainder << BASE_BITS) | *limb as u64;
+      *limb = (current / divisor as u64) as u32;
+      remainder = current % divisor as u64;
+    }
+    self.trim();
+
+    remainder as u32
+  }
+
+  pub fn rem_u64(&self, modulus: u64) -> u64 {
+    assert!(modulus != 0);
+
+    let mut remainder = 0_u128;
+    for limb in self.limbs.iter().rev() {
+      remainder = ((remainder << BASE_BITS) | *limb as u128) % modulus as u128;
+    }
+
+    remainder as u64
+  }
+
+  // Approximates self / other, both are scaled down to about the precision of an f64 first so huge
+  // numbers don't overflow.
+  pub fn ratio(&self, other: &BigUint) -> f64 {
+    let base = other.limbs.len().saturating_sub(3);
+    let approximate = |value: &BigUint| -> f64 {
+      value.limbs.iter().enumerate().skip(base)
+        .fold(0.0, |sum, (i, limb)| sum + *limb as f64 * 2_f64.powi(BASE_BITS as i32 * (i - base) as i32))
+    };
+
+    approximate(self) / approximate(other)
+  }
+}
+
+impl From<u64> for BigUint {
+  fn from(value: u64) -> BigUint {
+    let mut result = BigUint { limbs: vec![value as u32, (value >> BASE_BITS) as u32] };
+    result.trim();
+    result
+  }
+}
+
+impl Ord for BigUint {
+  fn cmp(&self, other: &BigUint) -> Ordering {
+    self.limbs.len().cmp(&other.limbs.len())
+      .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+  }
+}
+
+impl PartialOrd for BigUint {
+  fn partial_cmp(&self, other: &BigUint) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Add<&BigUint> for &BigUint {
+  type Output = BigUint;
+
+  fn add(self, other: &BigUint) -> BigUint {
+    let len = self.limbs.len().max(other.limbs.len());
+    let mut limbs = Vec::with_capacity(len + 1);
+
+    let mut carry = 0_u64;
+    for i in 0..len {
+      let sum = *self.limbs.get(i).unwrap_or(&0) as u64 + *other.limbs.get(i).unwrap_or(&0) as u64 + carry;
+      limbs.push(sum as u32);
+      carry = sum >> BASE_BITS;
+    }
+
+    if carry != 0 {
+      limbs.push(carry as u32);
+    }
+
+    BigUint { limbs }
+  }
+}
+
+impl Add for BigUint {
+  type Output = BigUint;
+
+  fn add(self, other: BigUint) -> BigUint {
+    &self + &other
+  }
+}
+
+impl Sub<&BigUint> for &BigUint {
+  type Output = BigUint;
+
+  fn sub(self, other: &BigUint) -> BigUint {
+    assert!(*self >= *other, "BigUint subtraction underflow");
+
+    let mut limbs = Vec::with_capacity(self.limbs.len());
+
+    let mut borrow = 0_i64;
+    for i in 0..self.limbs.len() {
+      let mut diff = self.limbs[i] as i64 - *other.limbs.get(i).unwrap_or(&0) as i64 - borrow;
+      if diff < 0 {
+        diff += 1 << BASE_BITS;
+        borrow = 1;
+      } else {
+        borrow = 0;
+      }
+      limbs.push(diff as u32);
+    }
+
+    let mut result = BigUint { limbs };
+    result.trim();
+    result
+  }
+}
+
+impl Sub for BigUint {
+  type Output = BigUint;
+
+  fn sub(self, other: BigUint) -> BigUint {
+    &self - &other
+  }
+}
+
+impl Mul<&BigUint> for &BigUint {
+  type Output = BigUint;
+
+  fn mul(self, other: &BigUint) -> BigUint {
+    if self.is_zero() || other.is_zero() {
+      return BigUint::zero();
+    }
+
+    let mut limbs = vec![0_u32; self.limbs.len() + other.limbs.len()];
+
+    for (i, a) in self.limbs.iter().enumerate() {
+      let mut carry = 0_u64;
+      for (j, b) in other.limbs.iter().enumerate() {
+        let current = limbs[i + j] as u64 + (*a as u64) * (*b as u64) + carry;
+        limbs[i + j] = current as u32;
+        carry = current >> BASE_BITS;
+      }
+      limbs[i + other.limbs.len()] = carry as u32;
+    }
+
+    let mut result = BigUint { limbs };
+    result.trim();
+    result
+  }
+}
+
+impl Mul for BigUint {
+  type Output = BigUint;
+
+  fn mul(self, other: BigUint) -> BigUint {
+    &self * &other
+  }
+}
+
+impl std::iter::Sum for BigUint {
+  fn sum<I: Iterator<Item = BigUint>>(iter: I) -> BigUint {
+    iter.fold(BigUint::zero(), |acc, n| &acc + &n)
+  }
+}
+
+impl std::iter::Product for BigUint {
+  fn product<I: Iterator<Item = BigUint>>(iter: I) -> BigUint {
+    iter.fold(BigUint::one(), |acc, n| &acc * &n)
+  }
+}
+
+impl fmt::Display for BigUint {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.is_zero() {
+      return f.pad("0");
+    }
+
+    const CHUNK: u32 = 1_000_000_000;
+
+    let mut chunks = Vec::new();
+    let mut rest = self.clone();
+    while !rest.is_zero() {
+      chunks.push(rest.div_rem_small(CHUNK));
+    }
+
+    let mut result = chunks.last().unwrap().to_string();
+    for chunk in chunks.iter().rev().skip(1) {
+      result.push_str(&format!("{:09}", chunk));
+    }
+
+    f.pad(&result)
+  }
+}
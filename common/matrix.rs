@@ -0,0 +1,149 @@
+#![allow(dead_code)]
+
+use std::fmt;
+
+use crate::biguint::BigUint;
+
+// The numbers counts are kept in, exact or modulo some number
+pub trait Arithmetic {
+  type Value: Clone + PartialEq + fmt::Display;
+
+  fn zero(&self) -> Self::Value;
+  fn value(&self, value: u64) -> Self::Value;
+  fn is_zero(&self, value: &Self::Value) -> bool;
+  fn add(&self, a: &Self::Value, b: &Self::Value) -> Self::Value;
+  fn mul(&self, a: &Self::Value, b: &Self::Value) -> Self::Value;
+}
+
+// Exact counts
+pub struct Exact;
+
+impl Arithmetic for Exact {
+  type Value = BigUint;
+
+  fn zero(&self) -> BigUint {
+    BigUint::zero()
+  }
+
+  fn value(&self, value: u64) -> BigUint {
+    BigUint::from(value)
+  }
+
+  fn is_zero(&self, value: &BigUint) -> bool {
+    value.is_zero()
+  }
+
+  fn add(&self, a: &BigUint, b: &BigUint) -> BigUint {
+    a + b
+  }
+
+  fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+    a * b
+  }
+}
+
+// Counts modulo a number, for when exact counts don't fit in memory
+pub struct Modular(pub u64);
+
+impl Arithmetic for Modular {
+  type Value = u64;
+
+  fn zero(&self) -> u64 {
+    0
+  }
+
+  fn value(&self, value: u64) -> u64 {
+    value % self.0
+  }
+
+  fn is_zero(&self, value: &u64) -> bool {
+    *value == 0
+  }
+
+  fn add(&self, a: &u64, b: &u64) -> u64 {
+    ((*a as u128 + *b as u128) % self.0 as u128) as u64
+  }
+
+  fn mul(&self, a: &u64, b: &u64) -> u64 {
+    ((*a as u128 * *b as u128) % self.0 as u128) as u64
+  }
+}
+
+// Square matrix stored row by row
+pub struct Matrix<V> {
+  pub size: usize,
+  pub cells: Vec<V>,
+}
+
+impl<V: Clone> Matrix<V> {
+  // The matrix moving a count from each state to the states it leads to, `successors[from]` lists the
+  // states `from` leads to. A state may be listed more than once.
+  pub fn transitions<A: Arithmetic<Value = V>>(successors: &[Vec<usize>], arith: &A) -> Matrix<V> {
+    let size = successors.len();
+    let mut cells = vec![arith.zero(); size * size];
+
+    for (from, to) in successors.iter().enumerate() {
+      for state in to.iter() {
+        cells[state * size + from] = arith.add(&cells[state * size + from], &arith.value(1));
+      }
+    }
+
+    Matrix { size, cells }
+  }
+
+  pub fn identity<A: Arithmetic<Value = V>>(size: usize, arith: &A) -> Matrix<V> {
+    let mut cells = vec![arith.zero(); size * size];
+    for i in 0..size {
+      cells[i * size + i] = arith.value(1);
+    }
+
+    Matrix { size, cells }
+  }
+
+  pub fn mul<A: Arithmetic<Value = V>>(&self, other: &Matrix<V>, arith: &A) -> Matrix<V> {
+    let size = self.size;
+    let mut cells = vec![arith.zero(); size * size];
+
+    for row in 0..size {
+      for k in 0..size {
+        let a = &self.cells[row * size + k];
+        // Transition matrices are sparse, and so are their lower powers
+        if arith.is_zero(a) {
+          continue;
+        }
+
+        for col in 0..size {
+          let b = &other.cells[k * size + col];
+          if !arith.is_zero(b) {
+            cells[row * size + col] = arith.add(&cells[row * size + col], &arith.mul(a, b));
+          }
+        }
+      }
+    }
+
+    Matrix { size, cells }
+  }
+
+  pub fn apply<A: Arithmetic<Value = V>>(&self, vector: &[V], arith: &A) -> Vec<V> {
+    (0..self.size).map(|row| {
+      (0..self.size).fold(arith.zero(), |sum, col| arith.add(&sum, &arith.mul(&self.cells[row * self.size + col], &vector[col])))
+    }).collect()
+  }
+
+  pub fn pow<A: Arithmetic<Value = V>>(&self, mut exponent: u64, arith: &A) -> Matrix<V> {
+    let mut result = Matrix::identity(self.size, arith);
+    let mut base = Matrix { size: self.size, cells: self.cells.clone() };
+
+    while exponent > 0 {
+      if exponent & 1 == 1 {
+        result = result.mul(&base, arith);
+      }
+      exponent >>= 1;
+      if exponent > 0 {
+        base = base.mul(&base, arith);
+      }
+    }
+
+    result
+  }
+}
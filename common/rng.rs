@@ -0,0 +1,26 @@
+#![allow(dead_code)]
+
+// SplitMix64, the same seed always gives the same numbers. Not meant for anything that has to be
+// unpredictable.
+pub struct Rng {
+  state: u64,
+}
+
+impl Rng {
+  pub fn new(seed: u64) -> Rng {
+    Rng { state: seed }
+  }
+
+  pub fn next_u64(&mut self) -> u64 {
+    self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+
+    let mut z = self.state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+  }
+
+  pub fn below(&mut self, bound: u64) -> u64 {
+    self.next_u64() % bound
+  }
+}
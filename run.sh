@@ -14,6 +14,7 @@ input_filename="input.dat"
 folder="$(pwd)"
 bin=""
 verbosity="--quiet"
+extra_args=()
 
 while [ $# -gt 0 ]; do
   case "$1" in
@@ -23,6 +24,11 @@ while [ $# -gt 0 ]; do
     -v|--verbose)
       verbosity=""
       ;;
+    --)
+      shift
+      extra_args=("$@")
+      break
+      ;;
     *)
       folder="$folder/$1"
       bin="$bin-$1"
@@ -32,7 +38,7 @@ while [ $# -gt 0 ]; do
 done
 
 if [ -z $bin ]; then
-  echo "Usage: run.sh [--example, -e] [--verbose, -v] <day> [part]" >&2
+  echo "Usage: run.sh [--example, -e] [--verbose, -v] <day> [part] [-- <program options>]" >&2
   echo "" >&2
   echo "Options:" >&2
   echo "  --example, -e         Use the example data given in the exercise description" >&2
@@ -41,6 +47,7 @@ if [ -z $bin ]; then
   echo "Examples:" >&2
   echo "  run.sh 01 01" >&2
   echo "  run.sh 24" >&2
+  echo "  run.sh -e 18 01 -- --trace" >&2
   exit 1
 fi
 
@@ -49,4 +56,4 @@ if ! [ -f "$folder/$input_filename" ]; then
   exit 1
 fi
 
-cargo run $verbosity --bin "${bin:1}" "$folder/$input_filename"
+cargo run $verbosity --bin "${bin:1}" "$folder/$input_filename" "${extra_args[@]}"